@@ -0,0 +1,329 @@
+//! AocParse
+//! `#[derive(AocParse)]` generates a [std::str::FromStr] for a struct from a
+//! format string that describes one line of puzzle input:
+//!
+//! ```rust,ignore
+//! use utils::AocParse;
+//!
+//! #[derive(AocParse)]
+//! #[aoc("Card {id}: {winners: ws} | {haves: ws}")]
+//! struct Card {
+//!     id: usize,
+//!     winners: Vec<usize>,
+//!     haves: Vec<usize>,
+//! }
+//!
+//! let card: Card = "Card 1: 41 48 83 | 83 86  6".parse()?;
+//! ```
+//!
+//! Each `{field}` is parsed with the field type's `FromStr`.  A field can
+//! also be a list, collected into any `FromIterator`:
+//! - `{field: ws}` splits on whitespace
+//! - `{field: sep(;)}` splits on the text inside the parens
+//!
+//! `{_}` matches text that isn't kept.
+//!
+//! Whitespace in the format matches any amount of whitespace in the line.
+//! Use `{{` and `}}` for literal braces.  Fields that aren't in the format
+//! must be marked `#[aoc(default)]`, and are set with [Default].  A raw
+//! field such as `r#type` is written `{type}`.
+//!
+//! The generated code uses `utils::LineParser`, and errors are
+//! `anyhow::Error`s that give the column where parsing failed.  Use the
+//! derive through `utils::AocParse`.
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{ext::IdentExt, parse_macro_input, Data, DeriveInput, Fields, Ident, LitStr};
+
+#[proc_macro_derive(AocParse, attributes(aoc))]
+pub fn derive_aoc_parse(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// How a field's text becomes a value
+#[derive(Debug, PartialEq)]
+enum Kind {
+    /// `FromStr` on the whole text
+    Value,
+    /// Split on whitespace
+    Words,
+    /// Split on the given text
+    Separated(String),
+}
+
+#[derive(Debug, PartialEq)]
+enum Segment {
+    Literal(String),
+    Field(String, Kind),
+    /// `{_}`: text that is matched but not kept
+    Skip,
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "AocParse only supports structs",
+        ));
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "AocParse only supports structs with named fields",
+        ));
+    };
+
+    let format = input
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("aoc"))
+        .ok_or_else(|| {
+            syn::Error::new_spanned(&input.ident, "Missing #[aoc(\"...\")] format string")
+        })?
+        .parse_args::<LitStr>()?;
+    let segments =
+        parse_format(&format.value()).map_err(|msg| syn::Error::new_spanned(&format, msg))?;
+
+    // Every field in the format must exist, once
+    let mut seen: Vec<&str> = Vec::new();
+    for segment in &segments {
+        if let Segment::Field(field, _) = segment {
+            if seen.contains(&field.as_str()) {
+                let msg = format!("Field `{}` is in the format more than once", field);
+                return Err(syn::Error::new_spanned(&format, msg));
+            }
+            if named(fields, field).is_none() {
+                let msg = format!("`{}` has no field `{}`", name, field);
+                return Err(syn::Error::new_spanned(&format, msg));
+            }
+            seen.push(field);
+        }
+    }
+
+    // Every field in the struct must be in the format, or marked default
+    let mut defaults = Vec::new();
+    for field in &fields.named {
+        let ident = field.ident.as_ref().expect("Named field");
+        if seen.iter().any(|s| ident.unraw() == s) {
+            continue;
+        }
+        if !is_default(field)? {
+            let msg = format!(
+                "Field `{}` is not in the format; add it or mark it #[aoc(default)]",
+                ident
+            );
+            return Err(syn::Error::new_spanned(ident, msg));
+        }
+        defaults.push(ident);
+    }
+
+    let mut steps = Vec::new();
+    for (idx, segment) in segments.iter().enumerate() {
+        match segment {
+            Segment::Literal(literal) => steps.push(quote! { parser.literal(#literal)?; }),
+            Segment::Skip => {
+                let until = until(&segments, idx);
+                steps.push(quote! { parser.take(#until); });
+            }
+            Segment::Field(field, kind) => {
+                let until = until(&segments, idx);
+                let named = named(fields, field).expect("Checked above");
+                let ident = &named.ident;
+                let ty = &named.ty;
+                let value = match kind {
+                    Kind::Value => quote! { parser.value(#field, text)? },
+                    Kind::Words => quote! { parser.list(#field, text, None)? },
+                    Kind::Separated(sep) => quote! { parser.list(#field, text, Some(#sep))? },
+                };
+                steps.push(quote! {
+                    let text = parser.take(#until);
+                    let #ident: #ty = #value;
+                });
+            }
+        }
+    }
+    let assigned = seen
+        .iter()
+        .map(|field| &named(fields, field).expect("Checked above").ident);
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::std::str::FromStr for #name #ty_generics #where_clause {
+            type Err = ::anyhow::Error;
+
+            fn from_str(line: &str) -> ::std::result::Result<Self, Self::Err> {
+                let mut parser = ::utils::LineParser::new(stringify!(#name), line);
+                #(#steps)*
+                parser.finish()?;
+                Ok(Self {
+                    #(#assigned,)*
+                    #(#defaults: ::std::default::Default::default(),)*
+                })
+            }
+        }
+    })
+}
+
+/// The struct field a format placeholder names.  Raw fields match without
+/// their `r#`, so the generated code uses the struct's own identifier.
+fn named<'a>(fields: &'a syn::FieldsNamed, name: &str) -> Option<&'a syn::Field> {
+    fields
+        .named
+        .iter()
+        .find(|f| f.ident.as_ref().is_some_and(|i| i.unraw() == name))
+}
+
+/// The literal that ends the field at `idx`, if any
+fn until(segments: &[Segment], idx: usize) -> TokenStream2 {
+    match segments.get(idx + 1) {
+        Some(Segment::Literal(literal)) => quote! { Some(#literal) },
+        _ => quote! { None },
+    }
+}
+
+/// True if the field is marked `#[aoc(default)]`
+fn is_default(field: &syn::Field) -> syn::Result<bool> {
+    let Some(attr) = field.attrs.iter().find(|attr| attr.path().is_ident("aoc")) else {
+        return Ok(false);
+    };
+    let ident = attr.parse_args::<Ident>()?;
+    if ident != "default" {
+        return Err(syn::Error::new_spanned(ident, "Expected #[aoc(default)]"));
+    }
+    Ok(true)
+}
+
+/// Split a format string into literals and fields
+fn parse_format(format: &str) -> Result<Vec<Segment>, String> {
+    let mut segments = Vec::new();
+    let mut literal = String::new();
+    let mut chars = format.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                literal.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                literal.push('}');
+            }
+            '}' => return Err("Unmatched `}`; use `}}` for a literal brace".to_string()),
+            '{' => {
+                let mut spec = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => spec.push(c),
+                        None => return Err("Unclosed `{`".to_string()),
+                    }
+                }
+                if !literal.is_empty() {
+                    segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                } else if matches!(segments.last(), Some(Segment::Field(..) | Segment::Skip)) {
+                    return Err(format!(
+                        "Field `{}` must be separated from the previous field",
+                        spec
+                    ));
+                }
+                segments.push(parse_field(&spec)?);
+            }
+            _ => literal.push(c),
+        }
+    }
+    if !literal.is_empty() {
+        segments.push(Segment::Literal(literal));
+    }
+    Ok(segments)
+}
+
+/// Parse `_`, `name` or `name: kind`
+fn parse_field(spec: &str) -> Result<Segment, String> {
+    if spec.trim() == "_" {
+        return Ok(Segment::Skip);
+    }
+    let (name, kind) = match spec.split_once(':') {
+        Some((name, kind)) => (name.trim(), kind.trim()),
+        None => (spec.trim(), ""),
+    };
+    if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        return Err(format!("Invalid field name `{}`", name));
+    }
+    let kind = match kind {
+        "" => Kind::Value,
+        "ws" => Kind::Words,
+        _ => match kind.strip_prefix("sep(").and_then(|s| s.strip_suffix(')')) {
+            Some(sep) if !sep.is_empty() => Kind::Separated(sep.to_string()),
+            _ => {
+                return Err(format!(
+                    "Unknown kind `{}` for `{}`; expected `ws` or `sep(...)`",
+                    kind, name
+                ))
+            }
+        },
+    };
+    Ok(Segment::Field(name.to_string(), kind))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_format() {
+        let segments = parse_format("Card {id}: {winners: ws} | {haves:sep(, )}").unwrap();
+        assert_eq!(
+            segments,
+            vec![
+                Segment::Literal("Card ".to_string()),
+                Segment::Field("id".to_string(), Kind::Value),
+                Segment::Literal(": ".to_string()),
+                Segment::Field("winners".to_string(), Kind::Words),
+                Segment::Literal(" | ".to_string()),
+                Segment::Field("haves".to_string(), Kind::Separated(", ".to_string())),
+            ]
+        );
+
+        let segments = parse_format("{{{a}}}").unwrap();
+        assert_eq!(segments[0], Segment::Literal("{".to_string()));
+        assert_eq!(segments[2], Segment::Literal("}".to_string()));
+
+        let segments = parse_format("{_}: {a}").unwrap();
+        assert_eq!(segments[0], Segment::Skip);
+
+        assert!(parse_format("{a}{b}").is_err());
+        assert!(parse_format("{a}{_}").is_err());
+        assert!(parse_format("{a").is_err());
+        assert!(parse_format("a}").is_err());
+        assert!(parse_format("{a: csv}").is_err());
+        assert!(parse_format("{a-b}").is_err());
+    }
+
+    #[test]
+    fn test_keyword_field() {
+        let input: DeriveInput = syn::parse_quote! {
+            #[aoc("{type} {value}")]
+            struct Entry {
+                r#type: String,
+                value: usize,
+            }
+        };
+        let expanded = expand(input).unwrap().to_string();
+        assert!(expanded.contains("let r#type"));
+
+        // A keyword placeholder without a matching field is an error
+        let input: DeriveInput = syn::parse_quote! {
+            #[aoc("{type}")]
+            struct Entry {
+                value: usize,
+            }
+        };
+        assert!(expand(input).is_err());
+    }
+}
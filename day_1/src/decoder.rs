@@ -0,0 +1,243 @@
+//! Decoder
+//! Finds the first and last digit tokens in a line in one pass, using an
+//! Aho–Corasick automaton.  Tokens may overlap, so `eightwo` decodes to a
+//! first digit of 8 and a last digit of 2.  The vocabulary is anything that
+//! maps strings to values: plain digits, spelled out words, another
+//! language, or custom tokens.
+//!
+//! Building the automaton is linear in the total length of the tokens, and
+//! decoding a line is linear in its length plus the number of matches.
+use std::cmp::Reverse;
+use std::collections::{HashMap, VecDeque};
+
+use anyhow::{anyhow, Result};
+
+const DIGITS: [(&str, u32); 10] = [
+    ("0", 0),
+    ("1", 1),
+    ("2", 2),
+    ("3", 3),
+    ("4", 4),
+    ("5", 5),
+    ("6", 6),
+    ("7", 7),
+    ("8", 8),
+    ("9", 9),
+];
+
+const ENGLISH: [(&str, u32); 10] = [
+    ("zero", 0),
+    ("one", 1),
+    ("two", 2),
+    ("three", 3),
+    ("four", 4),
+    ("five", 5),
+    ("six", 6),
+    ("seven", 7),
+    ("eight", 8),
+    ("nine", 9),
+];
+
+/// A token found in a line.  `start` and `end` are char offsets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Match {
+    pub start: usize,
+    pub end: usize,
+    pub value: u32,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    next: HashMap<char, usize>,
+    /// Longest suffix of this state that is also a state
+    fail: usize,
+    /// The token ending at this state, as (length in chars, value)
+    token: Option<(usize, u32)>,
+    /// Nearest state along the fail links that ends a token
+    output: Option<usize>,
+}
+
+#[derive(Debug)]
+pub struct Decoder {
+    states: Vec<State>,
+}
+
+impl Decoder {
+    /// Build a decoder for `(token, value)` pairs.  Tokens must not be empty
+    /// and must not repeat.
+    pub fn new<'a>(vocabulary: impl IntoIterator<Item = (&'a str, u32)>) -> Result<Self> {
+        let mut states = vec![State::default()];
+
+        // The trie
+        for (token, value) in vocabulary {
+            if token.is_empty() {
+                return Err(anyhow!("Empty token for {}", value));
+            }
+            let mut state = 0;
+            for c in token.chars() {
+                state = match states[state].next.get(&c) {
+                    Some(&next) => next,
+                    None => {
+                        states.push(State::default());
+                        let next = states.len() - 1;
+                        states[state].next.insert(c, next);
+                        next
+                    }
+                };
+            }
+            if states[state].token.is_some() {
+                return Err(anyhow!("Duplicate token: {}", token));
+            }
+            states[state].token = Some((token.chars().count(), value));
+        }
+
+        // Fail and output links, breadth first so shorter states are done first
+        let mut queue: VecDeque<usize> = states[0].next.values().copied().collect();
+        while let Some(state) = queue.pop_front() {
+            let edges = states[state]
+                .next
+                .iter()
+                .map(|(&c, &next)| (c, next))
+                .collect::<Vec<_>>();
+            for (c, next) in edges {
+                let mut fail = states[state].fail;
+                while fail != 0 && !states[fail].next.contains_key(&c) {
+                    fail = states[fail].fail;
+                }
+                let fail = states[fail].next.get(&c).copied().unwrap_or(0);
+                states[next].fail = fail;
+                states[next].output = match states[fail].token {
+                    Some(_) => Some(fail),
+                    None => states[fail].output,
+                };
+                queue.push_back(next);
+            }
+        }
+
+        Ok(Self { states })
+    }
+
+    /// The digits `0` - `9`
+    pub fn digits() -> Self {
+        Self::new(DIGITS).expect("Valid vocabulary")
+    }
+
+    /// The digits, and the English words `zero` - `nine`
+    pub fn english() -> Self {
+        Self::new(DIGITS.into_iter().chain(ENGLISH)).expect("Valid vocabulary")
+    }
+
+    /// Every token in `line`, including overlapping ones, in the order they
+    /// end.
+    pub fn matches<'a>(&'a self, line: &'a str) -> impl Iterator<Item = Match> + 'a {
+        let mut state = 0;
+        line.chars().enumerate().flat_map(move |(idx, c)| {
+            state = self.step(state, c);
+            let end = idx + 1;
+            let mut found = match self.states[state].token {
+                Some(_) => Some(state),
+                None => self.states[state].output,
+            };
+            std::iter::from_fn(move || {
+                let current = found?;
+                found = self.states[current].output;
+                let (len, value) = self.states[current].token.expect("Output ends a token");
+                Some(Match {
+                    start: end - len,
+                    end,
+                    value,
+                })
+            })
+        })
+    }
+
+    /// The values of the first and last tokens in `line`, by where they
+    /// start.  When tokens start at the same place, the longest wins.  A
+    /// line with one token gives it as both first and last.
+    pub fn first_last(&self, line: &str) -> Option<(u32, u32)> {
+        let mut first: Option<Match> = None;
+        let mut last: Option<Match> = None;
+        for found in self.matches(line) {
+            // Matches come out by end, so the first to start may come later
+            let key = (found.start, Reverse(found.end));
+            if first.is_none_or(|first| key < (first.start, Reverse(first.end))) {
+                first = Some(found);
+            }
+            if last.is_none_or(|last| (found.start, found.end) > (last.start, last.end)) {
+                last = Some(found);
+            }
+        }
+        Some((first?.value, last?.value))
+    }
+
+    fn step(&self, mut state: usize, c: char) -> usize {
+        loop {
+            if let Some(&next) = self.states[state].next.get(&c) {
+                return next;
+            }
+            if state == 0 {
+                return 0;
+            }
+            state = self.states[state].fail;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overlaps() {
+        let decoder = Decoder::english();
+        assert_eq!(decoder.first_last("eightwo"), Some((8, 2)));
+        assert_eq!(decoder.first_last("xtwone3four"), Some((2, 4)));
+        assert_eq!(decoder.first_last("zoneight234"), Some((1, 4)));
+        assert_eq!(decoder.first_last("7pqrstsixteen"), Some((7, 6)));
+        assert_eq!(decoder.first_last("oneight"), Some((1, 8)));
+
+        let matches = decoder.matches("twone").collect::<Vec<_>>();
+        assert_eq!(
+            matches,
+            vec![
+                Match {
+                    start: 0,
+                    end: 3,
+                    value: 2
+                },
+                Match {
+                    start: 2,
+                    end: 5,
+                    value: 1
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn test_single_digit() {
+        let decoder = Decoder::digits();
+        assert_eq!(decoder.first_last("treb7uchet"), Some((7, 7)));
+        assert_eq!(decoder.first_last("eightwo"), None);
+        assert_eq!(decoder.first_last(""), None);
+    }
+
+    #[test]
+    fn test_vocabulary() {
+        let french = [("un", 1), ("deux", 2), ("trois", 3), ("huit", 8)];
+        let decoder = Decoder::new(french).unwrap();
+        assert_eq!(decoder.first_last("xdeuxhuitroisun"), Some((2, 1)));
+
+        // A token inside a longer one, found by the output links
+        let decoder = Decoder::new([("seventeen", 17), ("teen", 13), ("even", 4)]).unwrap();
+        let values = decoder
+            .matches("seventeen")
+            .map(|m| m.value)
+            .collect::<Vec<_>>();
+        assert_eq!(values, vec![4, 17, 13]);
+        assert_eq!(decoder.first_last("seventeen"), Some((17, 13)));
+
+        assert!(Decoder::new([("", 0)]).is_err());
+        assert!(Decoder::new([("a", 0), ("a", 1)]).is_err());
+    }
+}
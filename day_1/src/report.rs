@@ -0,0 +1,152 @@
+//! Report
+//! Decodes every line of the calibration document and records what
+//! happened to each: the first and last digits and the value, or why the
+//! line was skipped.  [BadLines] decides whether a line without a value
+//! stops the run or is only reported.
+use anyhow::{anyhow, Result};
+
+use super::decoder::Decoder;
+
+/// What to do with a line that has no calibration value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BadLines {
+    /// Return an error for the first one
+    Fail,
+    /// Record it in the report, and leave it out of the total
+    Skip,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkipReason {
+    Blank,
+    NoDigits,
+}
+
+impl std::fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Blank => write!(f, "blank line"),
+            Self::NoDigits => write!(f, "no digits"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Value { first: u32, last: u32, value: u32 },
+    Skipped(SkipReason),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineReport<'a> {
+    /// 1 based
+    pub number: usize,
+    pub text: &'a str,
+    pub outcome: Outcome,
+}
+
+#[derive(Debug)]
+pub struct Report<'a> {
+    pub lines: Vec<LineReport<'a>>,
+}
+
+impl<'a> Report<'a> {
+    pub fn new(input: &'a str, decoder: &Decoder, bad_lines: BadLines) -> Result<Self> {
+        let mut lines = Vec::new();
+        for (idx, text) in input.lines().enumerate() {
+            let outcome = match decoder.first_last(text) {
+                Some((first, last)) => Outcome::Value {
+                    first,
+                    last,
+                    value: first * 10 + last,
+                },
+                None if text.trim().is_empty() => Outcome::Skipped(SkipReason::Blank),
+                None => Outcome::Skipped(SkipReason::NoDigits),
+            };
+            if let (Outcome::Skipped(reason), BadLines::Fail) = (outcome, bad_lines) {
+                return Err(anyhow!("Line {}: {}: {:?}", idx + 1, reason, text));
+            }
+            lines.push(LineReport {
+                number: idx + 1,
+                text,
+                outcome,
+            });
+        }
+        Ok(Self { lines })
+    }
+
+    /// Sum of the calibration values
+    pub fn total(&self) -> usize {
+        self.lines
+            .iter()
+            .filter_map(|line| match line.outcome {
+                Outcome::Value { value, .. } => Some(value as usize),
+                Outcome::Skipped(_) => None,
+            })
+            .sum()
+    }
+
+    pub fn skipped(&self) -> impl Iterator<Item = &LineReport<'a>> {
+        self.lines
+            .iter()
+            .filter(|line| matches!(line.outcome, Outcome::Skipped(_)))
+    }
+}
+
+impl<'a> std::fmt::Display for Report<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for line in &self.lines {
+            match line.outcome {
+                Outcome::Value { first, last, value } => writeln!(
+                    f,
+                    "{:>5}: {} {} = {:>2}  {}",
+                    line.number, first, last, value, line.text
+                )?,
+                Outcome::Skipped(reason) => {
+                    writeln!(f, "{:>5}: skipped, {}  {}", line.number, reason, line.text)?
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report() {
+        let input = "two1nine\n\nabc\neightwothree\n";
+        let decoder = Decoder::english();
+
+        let report = Report::new(input, &decoder, BadLines::Skip).unwrap();
+        assert_eq!(report.total(), 29 + 83);
+        assert_eq!(report.lines.len(), 4);
+        assert_eq!(
+            report.lines[3].outcome,
+            Outcome::Value {
+                first: 8,
+                last: 3,
+                value: 83
+            }
+        );
+        let skipped = report
+            .skipped()
+            .map(|line| (line.number, line.outcome))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            skipped,
+            vec![
+                (2, Outcome::Skipped(SkipReason::Blank)),
+                (3, Outcome::Skipped(SkipReason::NoDigits))
+            ]
+        );
+        assert!(report
+            .to_string()
+            .contains("    3: skipped, no digits  abc"));
+
+        let err = Report::new(input, &decoder, BadLines::Fail).unwrap_err();
+        assert_eq!(err.to_string(), "Line 2: blank line: \"\"");
+    }
+}
@@ -1,4 +1,4 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 
 #[derive(Debug, Default, Clone, PartialEq)]
 enum Connections {
@@ -257,6 +257,21 @@ impl Pipes {
         }
         points
     }
+
+    /// Write the map to `path` as a PGM image: the loop is white, the tiles
+    /// it encloses are grey and everything else is black.
+    fn write_pgm(&self, path: &str) -> Result<()> {
+        let mut shades = vec![0u8; self.max_x * self.max_y];
+        for point in self.find_area() {
+            shades[point.y * self.max_x + point.x] = 0x80;
+        }
+        for point in self.measure_path() {
+            shades[point.y * self.max_x + point.x] = 0xff;
+        }
+        let grid = utils::Grid::<usize, u8>::from_cells(shades, self.max_x, self.max_y);
+        let mut out = std::io::BufWriter::new(std::fs::File::create(path)?);
+        grid.write_pgm(&mut out, |shade| *shade)
+    }
 }
 
 /// Find the farthest
@@ -277,10 +292,17 @@ fn part_two(pipes: &Pipes) -> Result<()> {
 }
 
 fn main() -> Result<()> {
+    let args = std::env::args().collect::<Vec<String>>();
     let input = include_str!("../puzzle_input.txt");
     let pipes = parse_input(input);
     part_one(&pipes)?;
     part_two(&pipes)?;
+    if let Some(idx) = args.iter().position(|arg| arg == "--pgm") {
+        let path = args
+            .get(idx + 1)
+            .ok_or_else(|| anyhow!("--pgm needs a path"))?;
+        pipes.write_pgm(path)?;
+    }
 
     Ok(())
 }
@@ -1,16 +1,16 @@
 /// Thanks to:
 /// https://github.com/maneatingape/advent-of-code-rust/blob/main/src/year2023/day12.rs
-use anyhow::{anyhow, Result};
-use utils::parse_nums;
+use anyhow::Result;
+use utils::AocParse;
 
-#[derive(Clone, Debug, PartialEq)]
-struct Record<'a> {
-    pattern: &'a [u8],
+#[derive(Clone, Debug, PartialEq, AocParse)]
+#[aoc("{pattern} {conditions: sep(,)}")]
+struct Record {
+    pattern: String,
     conditions: Vec<usize>,
 }
-impl<'a> std::fmt::Display for Record<'a> {
+impl std::fmt::Display for Record {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let pattern = String::from_utf8(self.pattern.to_vec()).unwrap();
         let nums = self
             .conditions
             .iter()
@@ -18,33 +18,20 @@ impl<'a> std::fmt::Display for Record<'a> {
             .collect::<Vec<String>>()
             .join(",");
 
-        writeln!(f, "{} {}", pattern, nums)
-    }
-}
-
-impl<'a> Record<'a> {
-    fn parse(value: &'a str) -> Result<Self> {
-        let (pattern, nums) = value
-            .split_once(' ')
-            .ok_or(anyhow!("Failed to parse Record"))?;
-        let conditions = parse_nums::<usize>(nums, ',')?;
-        Ok(Record {
-            pattern: pattern.as_bytes(),
-            conditions,
-        })
+        writeln!(f, "{} {}", self.pattern, nums)
     }
 }
 
 fn parse_input(input: &str) -> Result<Vec<Record>> {
     let mut records = vec![];
     for line in input.lines() {
-        records.push(Record::parse(line)?)
+        records.push(line.parse()?)
     }
 
     Ok(records)
 }
 
-fn solve(records: &Vec<Record>) -> Result<usize> {
+fn solve(records: &[Record]) -> Result<usize> {
     let result = 0;
     let mut pattern = Vec::new();
     let mut conditions = Vec::new();
@@ -57,7 +44,7 @@ fn solve(records: &Vec<Record>) -> Result<usize> {
 
         // Add a trailing '.' so that we don't have to check bounds when testing the last pattern.
         // This has no effect on the number of possible combinations.
-        pattern.extend_from_slice(record.pattern);
+        pattern.extend_from_slice(record.pattern.as_bytes());
         pattern.push(b'.');
         conditions.extend_from_slice(&record.conditions);
 
@@ -83,7 +70,7 @@ fn solve(records: &Vec<Record>) -> Result<usize> {
     Ok(result)
 }
 
-fn part_one(records: &Vec<Record>) -> Result<()> {
+fn part_one(records: &[Record]) -> Result<()> {
     let start = std::time::Instant::now();
     let result = solve(records)?;
 
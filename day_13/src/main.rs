@@ -1,8 +1,12 @@
 use anyhow::Result;
+use utils::sections;
 
-fn reduce(input: &[u8]) -> u32 {
+type Grid = utils::Grid<usize, u8>;
+
+/// Reduce a row (or column) to a number, treating '#' as a 1 bit.
+fn reduce<'a>(input: impl ExactSizeIterator<Item = &'a u8>) -> u32 {
     let len = input.len() as u32 - 1;
-    input.iter().enumerate().fold(0, |acc, (idx, c)| match c {
+    input.enumerate().fold(0, |acc, (idx, c)| match c {
         b'#' => acc + 2_u32.pow(len - idx as u32),
         _ => acc,
     })
@@ -40,26 +44,18 @@ fn line_of_reflection(values: &[u32]) -> Option<u32> {
     None
 }
 
-fn process_grid(grid: &[&[u8]]) -> u32 {
+fn process_grid(block: &str) -> Result<u32> {
     let mut result = 0;
-    let mut row_results: Vec<u32> = Vec::new();
-    let mut col_results: Vec<u32> = Vec::new();
-
-    for row in grid {
-        row_results.push(reduce(row));
-    }
+    let grid = Grid::parse_undelim_str(block, |c| Ok(c as u8))?;
 
-    // For each column
-    for x in 0..grid[0].len() {
-        // Calculate the value
-        col_results.push((0..grid.len()).fold(0, |acc, y| {
-            if grid[y][x] == b'#' {
-                acc + 2u32.pow((grid.len() - 1 - y) as u32)
-            } else {
-                acc
-            }
-        }));
-    }
+    let row_results = grid.view().rows().map(reduce).collect::<Vec<u32>>();
+    // The columns are the rows of the transposed grid
+    let col_results = grid
+        .view()
+        .transpose()
+        .rows()
+        .map(reduce)
+        .collect::<Vec<u32>>();
 
     if let Some(horizontal_line) = line_of_reflection(&row_results) {
         result += horizontal_line * 100;
@@ -68,27 +64,14 @@ fn process_grid(grid: &[&[u8]]) -> u32 {
     } else {
         eprintln!("No line of reflection found");
     }
-    result
+    Ok(result)
 }
 
 fn part_one(input: &str) -> Result<u32> {
     let timer = std::time::Instant::now();
     let mut result = 0;
-
-    let mut grid: Vec<&[u8]> = Vec::new();
-
-    for line in input.lines() {
-        if line.is_empty() {
-            // End of a set of lines.  Process and cler the grid.
-            result += process_grid(&grid);
-            grid.clear();
-            continue;
-        }
-        grid.push(line.as_bytes());
-    }
-    if !grid.is_empty() {
-        // Process the last set of lines
-        result += process_grid(&grid);
+    for block in sections(input) {
+        result += process_grid(block)?;
     }
 
     println!("Part One: {} -- {:?}", result, timer.elapsed());
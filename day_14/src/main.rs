@@ -1,7 +1,6 @@
 use anyhow::Result;
 use std::time::Instant;
-
-const GRID_SIZE: usize = 100;
+use utils::GridLike;
 
 #[derive(Debug, Clone, Copy, Default)]
 enum Direction {
@@ -30,13 +29,17 @@ impl From<char> for RockType {
     }
 }
 
-#[derive(Debug, Clone)]
-struct Grid(Vec<Vec<RockType>>);
+/// The puzzle input is 100 x 100, so it parses into an inline grid, with a
+/// heap allocated grid for any other size
+type Platform = utils::AnyGrid<usize, RockType, 100, 100>;
+
+#[derive(Debug)]
+struct Grid(utils::Grid<usize, RockType>);
 
 impl std::fmt::Display for Grid {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut s = String::new();
-        for row in &self.0 {
+        for row in self.0.view().rows() {
             for rock in row {
                 match rock {
                     RockType::None => s.push('.'),
@@ -49,97 +52,67 @@ impl std::fmt::Display for Grid {
         write!(f, "{}", s)
     }
 }
-impl Grid {
-    /// Immutable tilt. Returns a copy of self, tilted north
-    fn tilt_north(&mut self) {
-        for x in 0..GRID_SIZE {
-            let mut y = 1;
-            while y < GRID_SIZE {
-                if self.0[y][x] == RockType::Rounded {
-                    let mut swap = y;
-                    while swap > 0 && self.0[swap - 1][x] == RockType::None {
-                        swap -= 1;
-                    }
-                    if swap < y {
-                        self.0[y][x] = RockType::None;
-                        self.0[swap][x] = RockType::Rounded;
-                    }
-                }
-                y += 1;
-            }
-        }
-    }
 
-    /// Immutable tilt. Returns a copy of self, tilted north
-    fn tilt_south(&mut self) {
-        for x in 0..GRID_SIZE {
-            let mut y = GRID_SIZE - 2;
-            loop {
-                if self.0[y][x] == RockType::Rounded {
-                    let mut swap = y;
-                    while swap < GRID_SIZE - 1 && self.0[swap + 1][x] == RockType::None {
-                        swap += 1;
+/// Roll every rounded rock north, until it hits the edge or another rock.
+fn tilt_north<G: GridLike<RockType>>(grid: &mut G) {
+    for x in 0..grid.width() {
+        // The row the next rounded rock will roll to
+        let mut stop = 0;
+        for y in 0..grid.height() {
+            match *grid.get_at(x, y).expect("In bounds") {
+                RockType::Cubed => stop = y + 1,
+                RockType::Rounded => {
+                    if stop < y {
+                        *grid.get_at_mut(x, y).expect("In bounds") = RockType::None;
+                        *grid.get_at_mut(x, stop).expect("In bounds") = RockType::Rounded;
                     }
-                    if swap > y {
-                        self.0[y][x] = RockType::None;
-                        self.0[swap][x] = RockType::Rounded;
-                    }
-                }
-                if y == 0 {
-                    break;
+                    stop += 1;
                 }
-                y -= 1;
+                RockType::None => {}
             }
         }
     }
+}
 
-    fn tilt_east(&mut self) {
-        for y in 0..GRID_SIZE {
-            let mut x = GRID_SIZE - 2;
-            loop {
-                if self.0[y][x] == RockType::Rounded {
-                    let mut swap = x;
-                    while swap < GRID_SIZE - 1 && self.0[y][swap + 1] == RockType::None {
-                        swap += 1;
-                    }
-                    if swap > x {
-                        self.0[y][x] = RockType::None;
-                        self.0[y][swap] = RockType::Rounded;
-                    }
-                }
-                if x == 0 {
-                    break;
-                }
-                x -= 1;
+/// Each rounded rock counts for its distance from the south edge
+fn load<G: GridLike<RockType>>(grid: &G) -> usize {
+    let height = grid.height();
+    let mut result = 0;
+    for y in 0..height {
+        for x in 0..grid.width() {
+            if *grid.get_at(x, y).expect("In bounds") == RockType::Rounded {
+                result += height - y;
             }
         }
     }
+    result
+}
 
-    fn tilt_west(&mut self) {
-        for y in 0..GRID_SIZE {
-            let mut x = 1;
-            while x < GRID_SIZE {
-                if self.0[y][x] == RockType::Rounded {
-                    let mut swap = x;
-                    while swap > 0 && self.0[y][swap - 1] == RockType::None {
-                        swap -= 1;
-                    }
-                    if swap < x {
-                        self.0[y][x] = RockType::None;
-                        self.0[y][swap] = RockType::Rounded;
-                    }
-                }
-                x += 1;
-            }
-        }
+impl Grid {
+    fn tilt_north(&mut self) {
+        tilt_north(&mut self.0)
     }
 
+    /// Every direction is a north tilt, on a grid turned so that
+    /// `direction` faces north.
     fn tilt(&mut self, direction: Direction) {
         match direction {
             Direction::North => self.tilt_north(),
-            Direction::West => self.tilt_west(),
-            Direction::South => self.tilt_south(),
-            Direction::East => self.tilt_east(),
+            Direction::West => {
+                self.0 = self.0.transpose();
+                self.tilt_north();
+                self.0 = self.0.transpose();
+            }
+            Direction::South => {
+                self.0 = self.0.flip_v();
+                self.tilt_north();
+                self.0 = self.0.flip_v();
+            }
+            Direction::East => {
+                self.0 = self.0.rotate_ccw();
+                self.tilt_north();
+                self.0 = self.0.rotate_cw();
+            }
         }
     }
 
@@ -151,34 +124,26 @@ impl Grid {
     }
 
     fn calc_load(&self) -> usize {
-        let mut result = 0;
-        for (y, row) in self.0.iter().enumerate() {
-            for rock in row {
-                if *rock == RockType::Rounded {
-                    result += GRID_SIZE - y;
-                }
-            }
-        }
-        result
+        load(&self.0)
     }
 }
 
 fn part_one(input: &str) -> Result<()> {
     let timer = Instant::now();
-    let mut grid = parse_grid(input)?;
-    grid.tilt(Direction::North);
-    let result = grid.calc_load();
+    let mut platform = parse_platform(input)?;
+    tilt_north(&mut platform);
+    let result = load(&platform);
     println!("Part One: {} -- {:?}", result, timer.elapsed());
     Ok(())
 }
 
 fn part_two(input: &str) -> Result<()> {
     // Cycle 1_000_000_000 times... uh... no.
-    // Since the state is constant after GRID_SIZE cycles...
+    // Since the state is constant after as many cycles as the grid is tall...
     let timer = Instant::now();
     let mut grid = parse_grid(input)?;
 
-    for _x in 0..GRID_SIZE {
+    for _x in 0..grid.0.height() {
         grid.cycle();
     }
 
@@ -194,13 +159,46 @@ fn main() -> Result<()> {
     Ok(())
 }
 
+fn parse_platform(input: &str) -> Result<Platform> {
+    Platform::parse_undelim_str(input, |c| Ok(RockType::from(c)))
+}
+
+/// Tilting in every direction needs the views of a full [utils::Grid]
 fn parse_grid(input: &str) -> Result<Grid> {
-    let mut grid = vec![vec![RockType::None; GRID_SIZE]; GRID_SIZE];
-    for (y, line) in input.lines().enumerate() {
-        for (x, c) in line.chars().enumerate() {
-            grid[y][x] = RockType::from(c);
-        }
+    Ok(Grid(parse_platform(input)?.into_grid()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "O....#....
+O.OO#....#
+.....##...
+OO.#O....O
+.O.....O#.
+O.#..O.#.#
+..O..#O..O
+.......O..
+#....###..
+#OO..#....";
+
+    #[test]
+    fn test_tilt() {
+        let mut platform = parse_platform(EXAMPLE).unwrap();
+        assert!(matches!(platform, Platform::Dynamic(_)));
+        tilt_north(&mut platform);
+        assert_eq!(load(&platform), 136);
+
+        let mut grid = parse_grid(EXAMPLE).unwrap();
+        grid.tilt(Direction::North);
+        assert_eq!(grid.calc_load(), 136);
     }
 
-    Ok(Grid(grid))
+    #[test]
+    fn test_puzzle_input() {
+        let platform = parse_platform(include_str!("../puzzle_input.txt")).unwrap();
+        assert!(matches!(platform, Platform::Array(_)));
+        assert_eq!(platform.width(), 100);
+    }
 }
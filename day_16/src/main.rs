@@ -1,15 +1,12 @@
 /// Thanks to https://github.com/clearlyMine for the hints on tracking visited cells
-use anyhow::Result;
-use lazy_static::lazy_static;
+use anyhow::{anyhow, Result};
 use std::{collections::HashSet, time::Instant};
-use utils::Direction;
-const GRID_SIZE: u32 = 110;
-
-lazy_static! {
-    static ref GRID_BOUNDS: Point = Point::new((GRID_SIZE - 1) as i32, (GRID_SIZE - 1) as i32);
-}
+use utils::{Direction, GridLike, Walker};
 
 type Point = utils::Point<i32>;
+/// The puzzle input is 110 x 110, so it's kept inline.  Other sizes, like
+/// the tests, fall back to a heap allocated grid.
+type Grid = utils::AnyGrid<i32, TileType, 110, 110>;
 
 #[derive(Clone, Debug, Default, PartialEq)]
 enum TileType {
@@ -46,18 +43,22 @@ impl TileType {
     }
 }
 
-fn shine_beam(
-    grid: &[Vec<TileType>],
+/// Return every tile the beam passes through
+fn energize(
+    grid: &Grid,
     initial_position: Point,
     initial_direction: Direction,
-) -> Result<u32> {
-    let mut queue: Vec<(Point, Direction)> = vec![(initial_position, initial_direction)];
+) -> Result<HashSet<Point>> {
+    // Each split in the beam is a separate walker
+    let mut queue: Vec<Walker<i32, TileType>> =
+        vec![grid.walker(initial_position, initial_direction)];
 
     let mut visited: HashSet<(Point, Direction)> = HashSet::new();
     visited.insert((initial_position, initial_direction));
 
-    while let Some((position, direction)) = queue.pop() {
-        let new_directions: Vec<Direction> = match grid[position.y as usize][position.x as usize] {
+    while let Some(beam) = queue.pop() {
+        let direction = beam.heading();
+        let new_directions: Vec<Direction> = match beam.get()? {
             TileType::LeftRight => match direction {
                 // \
                 Direction::East => vec![Direction::South],
@@ -85,23 +86,43 @@ fn shine_beam(
 
         for direction in new_directions {
             // Move to the next point, based on the direction
-            let new_position = position.step(direction);
+            let mut next = beam.fork(direction);
             // If the point is in bounds and hasn't been  visited...
-            if new_position.bounded_z(&GRID_BOUNDS) && !visited.contains(&(new_position, direction))
-            {
-                visited.insert((new_position, direction));
-                queue.push((new_position, direction));
+            if let Some(new_position) = next.step() {
+                if visited.insert((new_position, direction)) {
+                    queue.push(next);
+                }
             }
         }
     }
 
-    let visited_points = visited.iter().map(|(p, _)| *p).collect::<HashSet<Point>>();
-    //show_energized(&grid, &visited_points);
+    Ok(visited.iter().map(|(p, _)| *p).collect::<HashSet<Point>>())
+}
 
-    Ok(visited_points.len() as u32)
+fn shine_beam(grid: &Grid, initial_position: Point, initial_direction: Direction) -> Result<u32> {
+    Ok(energize(grid, initial_position, initial_direction)?.len() as u32)
 }
 
-fn part_one(grid: &[Vec<TileType>]) -> Result<()> {
+/// Write the grid to `path` as a PGM image: energized tiles are white,
+/// mirrors and splitters grey, and empty tiles black.
+fn show_energized(grid: &Grid, energized: &HashSet<Point>, path: &str) -> Result<()> {
+    let shades = (0..grid.height())
+        .flat_map(|y| (0..grid.width()).map(move |x| (x, y)))
+        .map(|(x, y)| {
+            let energized = energized.contains(&Point::new(x as i32, y as i32));
+            grid.get_at(x, y).map(|tile| match tile {
+                _ if energized => 0xff,
+                TileType::Empty => 0,
+                _ => 0x60,
+            })
+        })
+        .collect::<Result<Vec<u8>>>()?;
+    let image = utils::Grid::<i32, u8>::from_cells(shades, grid.width(), grid.height());
+    let mut out = std::io::BufWriter::new(std::fs::File::create(path)?);
+    image.write_pgm(&mut out, |shade| *shade)
+}
+
+fn part_one(grid: &Grid) -> Result<()> {
     let timer = Instant::now();
 
     let position = Point::origin();
@@ -113,43 +134,89 @@ fn part_one(grid: &[Vec<TileType>]) -> Result<()> {
     Ok(())
 }
 
-fn part_two(grid: &[Vec<TileType>]) -> Result<()> {
-    let timer = Instant::now();
+/// The most tiles energized by a beam entering from any edge tile, including
+/// the last row and column
+fn most_energized(grid: &Grid) -> Result<u32> {
     let mut result = 0;
 
-    for i in 0..GRID_SIZE as i32 {
+    let bounds = grid.bounds();
+
+    for x in 0..=bounds.x {
         // Any point on first row
         result = std::cmp::max(
             result,
-            shine_beam(grid, Point::new(i, 0), Direction::South)?,
+            shine_beam(grid, Point::new(x, 0), Direction::South)?,
         );
 
-        // Any point on first col
-        result = std::cmp::max(result, shine_beam(grid, Point::new(0, i), Direction::East)?);
-    }
-
-    for i in (0..GRID_SIZE as i32).rev() {
         // Any point on last row
         result = std::cmp::max(
             result,
-            shine_beam(grid, Point::new(i, 0), Direction::North)?,
+            shine_beam(grid, Point::new(x, bounds.y), Direction::North)?,
         );
+    }
+
+    for y in 0..=bounds.y {
+        // Any point on first col
+        result = std::cmp::max(result, shine_beam(grid, Point::new(0, y), Direction::East)?);
 
         // Any point on last col
-        result = std::cmp::max(result, shine_beam(grid, Point::new(0, i), Direction::West)?);
+        result = std::cmp::max(
+            result,
+            shine_beam(grid, Point::new(bounds.x, y), Direction::West)?,
+        );
     }
+    Ok(result)
+}
+
+fn part_two(grid: &Grid) -> Result<()> {
+    let timer = Instant::now();
+    let result = most_energized(grid)?;
     println!("Part Two: {} -- {:?}", &result, timer.elapsed());
     Ok(())
 }
 
 fn main() -> Result<()> {
+    let args = std::env::args().collect::<Vec<String>>();
     let input = include_str!("../puzzle_input.txt");
-    let grid = input
-        .lines()
-        .map(|line| line.chars().map(TileType::from).collect::<Vec<TileType>>())
-        .collect::<Vec<Vec<TileType>>>();
+    let grid = Grid::parse_undelim_str(input, |c| Ok(TileType::from(c)))?;
 
     part_one(&grid)?;
     part_two(&grid)?;
+    if let Some(idx) = args.iter().position(|arg| arg == "--pgm") {
+        let path = args
+            .get(idx + 1)
+            .ok_or_else(|| anyhow!("--pgm needs a path"))?;
+        let energized = energize(&grid, Point::origin(), Direction::East)?;
+        show_energized(&grid, &energized, path)?;
+    }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_last_column() {
+        // Only a beam heading West from the last column reaches the
+        // splitter side on, and lights up 4 tiles
+        let grid = Grid::parse_undelim_str("|..\n...", |c| Ok(TileType::from(c))).unwrap();
+        assert!(matches!(grid, Grid::Dynamic(_)));
+        assert_eq!(
+            shine_beam(&grid, Point::new(2, 0), Direction::West).unwrap(),
+            4
+        );
+        assert_eq!(most_energized(&grid).unwrap(), 4);
+    }
+
+    #[test]
+    fn test_puzzle_input() {
+        let input = include_str!("../puzzle_input.txt");
+        let grid = Grid::parse_undelim_str(input, |c| Ok(TileType::from(c))).unwrap();
+        assert!(matches!(grid, Grid::Array(_)));
+        assert_eq!(
+            shine_beam(&grid, Point::origin(), Direction::East).unwrap(),
+            6795
+        );
+    }
+}
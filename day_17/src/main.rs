@@ -1,26 +1,44 @@
 use std::collections::HashMap;
 
 use anyhow::{anyhow, Result};
-use utils::PriorityQueue;
+use utils::{dijkstra, BucketQueue, Direction, Frontier, IndexedPriorityQueue, MinQueue};
 
 type Point = utils::Point<i32>;
 type Grid = utils::Grid<i32, u32>;
 
 #[allow(dead_code)]
-fn breadth_first_search(grid: &mut Grid, goal: Point) -> Result<Vec<Point>> {
+fn breadth_first_search(grid: &Grid, start: Point, goal: Point) -> Result<Vec<Point>> {
     let mut frontier: Vec<Point> = Vec::with_capacity(grid.width() * grid.height());
     let mut came_from = HashMap::<Point, Option<Point>>::new();
     let mut cost = HashMap::<Point, u32>::new();
 
-    frontier.push(grid.current());
-    came_from.insert(grid.current(), None);
-    cost.insert(grid.current(), 0);
+    frontier.push(start);
+    came_from.insert(start, None);
+    cost.insert(start, 0);
 
     while let Some(current) = frontier.pop() {
         if current == goal {
             break;
         }
-        for point in grid.neighbors(&current, 3) {
+        // Anything up to 3 steps away in a straight line.  The frontier is a
+        // stack, so the answer depends on this order: farthest first, North,
+        // West, South then East.
+        let reachable = [
+            Direction::North,
+            Direction::West,
+            Direction::South,
+            Direction::East,
+        ]
+        .into_iter()
+        .flat_map(|direction| {
+            let mut ray = grid
+                .ray(&current, direction, |_| false)
+                .take(3)
+                .collect::<Vec<_>>();
+            ray.reverse();
+            ray
+        });
+        for point in reachable {
             came_from.entry(point).or_insert_with(|| {
                 frontier.push(point);
                 Some(current)
@@ -32,59 +50,71 @@ fn breadth_first_search(grid: &mut Grid, goal: Point) -> Result<Vec<Point>> {
     Ok(path)
 }
 
-#[allow(dead_code)]
-fn dijkstra_search(grid: &mut Grid, goal: Point) -> Result<Vec<Point>> {
-    let mut frontier: PriorityQueue<u32, Point> =
-        PriorityQueue::with_capacity(true, grid.width() * grid.height());
-    let mut came_from = HashMap::<Point, Option<Point>>::new();
-    let mut cost_so_far = HashMap::<Point, u32>::new();
+/// Cheapest heat loss from `start` to `goal`, using any [Frontier] for the
+/// queue.  Heat loss per block is 1 - 9, so a [BucketQueue] works.
+fn dijkstra_search<Q: Frontier<u32, Point>>(
+    grid: &Grid,
+    frontier: Q,
+    start: Point,
+    goal: Point,
+) -> Result<(u32, Vec<Point>)> {
+    let successors = |current: &Point| {
+        grid.neighbors(current)
+            .filter_map(|next| grid.get(&next).ok().map(|heat| (next, *heat)))
+            .collect::<Vec<_>>()
+    };
+    dijkstra(frontier, start, |point| *point == goal, successors).ok_or(anyhow!(
+        "No path from {} to {}",
+        start,
+        goal
+    ))
+}
 
-    frontier.push(0, grid.current());
-    came_from.insert(grid.current(), None);
-    cost_so_far.insert(grid.current(), 0);
+/// Time each kind of frontier on the same search, in microseconds per run
+fn benchmark(grid: &Grid, start: Point, goal: Point) -> Result<()> {
+    const RUNS: u32 = 10;
+    let capacity = grid.width() * grid.height();
 
-    while let Some((_heat, current)) = frontier.pop() {
-        if current == goal {
-            break;
-        }
-        /*
-        for next in graph.neighbors(current):
-             new_cost = cost_so_far[current] + graph.cost(current, next)
-             if next not in cost_so_far or new_cost < cost_so_far[next]:
-                cost_so_far[next] = new_cost
-                priority = new_cost
-                frontier.put(next, priority)
-                came_from[next] = current
-        */
-        let current_cost = cost_so_far.get(&current).unwrap_or(&0).to_owned();
-        for next in grid.neighbors(&current, 1) {
-            let next_heat = grid.get(&next)?;
-            let new_cost = current_cost + next_heat;
-            let next_cost = cost_so_far.get(&next).unwrap_or(&0).to_owned();
-            if !cost_so_far.contains_key(&next) || new_cost < next_cost {
-                cost_so_far.insert(next, new_cost);
-                frontier.push(new_cost, next);
-                came_from.insert(next, Some(current));
-            }
-        }
+    let timer = std::time::Instant::now();
+    let mut heat = 0;
+    for _ in 0..RUNS {
+        (heat, _) = dijkstra_search(grid, MinQueue::with_capacity(capacity), start, goal)?;
     }
+    println!(
+        "MinQueue: {} -- {}",
+        heat,
+        timer.elapsed().as_micros() / RUNS as u128
+    );
 
-    let path = extract_path(&came_from, goal);
-    display_grid(grid, &path);
-    Ok(path)
+    let timer = std::time::Instant::now();
+    for _ in 0..RUNS {
+        let frontier = IndexedPriorityQueue::with_capacity(capacity);
+        (heat, _) = dijkstra_search(grid, frontier, start, goal)?;
+    }
+    println!(
+        "IndexedPriorityQueue: {} -- {}",
+        heat,
+        timer.elapsed().as_micros() / RUNS as u128
+    );
+
+    let timer = std::time::Instant::now();
+    for _ in 0..RUNS {
+        // The largest step is 9, so only 10 buckets are ever live
+        (heat, _) = dijkstra_search(grid, BucketQueue::with_capacity(9), start, goal)?;
+    }
+    println!(
+        "BucketQueue: {} -- {}",
+        heat,
+        timer.elapsed().as_micros() / RUNS as u128
+    );
+    Ok(())
 }
 
 fn extract_path(came_from: &HashMap<Point, Option<Point>>, goal: Point) -> Vec<Point> {
     // Now walk backward
     let mut path: Vec<Point> = Vec::new();
     let mut next = &goal;
-    loop {
-        let Some(point) = came_from.get(next) else {
-            break;
-        };
-        let Some(point) = point else {
-            break;
-        };
+    while let Some(Some(point)) = came_from.get(next) {
         path.push(*point);
         next = point;
     }
@@ -112,26 +142,52 @@ fn display_grid(grid: &Grid, path: &[Point]) {
     println!("{}", result);
 }
 
+/// Write the grid to `path` as a PGM image: the hotter the block the
+/// brighter its grey, and the path is white.
+fn write_pgm(grid: &Grid, path: &[Point], file: &str) -> Result<()> {
+    let mut shades = Vec::with_capacity(grid.width() * grid.height());
+    for y in 0..grid.height() {
+        for x in 0..grid.width() {
+            let heat = grid.get_at(x, y)?;
+            match path.contains(&Point::from((x as i32, y as i32))) {
+                true => shades.push(0xff),
+                false => shades.push((heat * 0xc0 / 9) as u8),
+            }
+        }
+    }
+    let image = utils::Grid::<i32, u8>::from_cells(shades, grid.width(), grid.height());
+    let mut out = std::io::BufWriter::new(std::fs::File::create(file)?);
+    image.write_pgm(&mut out, |shade| *shade)
+}
+
 fn parse_input(puzzle_input: &str) -> Grid {
     let convert = |c: char| c.to_digit(10).ok_or(anyhow!("Failed to convert"));
     Grid::parse_undelim_str(puzzle_input, convert).unwrap()
 }
 
-fn part_one(puzzle_input: &str) -> Result<()> {
+fn part_one(puzzle_input: &str, pgm: Option<&String>, benchmarks: bool) -> Result<()> {
     let timer = std::time::Instant::now();
-    let mut grid = parse_input(puzzle_input);
+    let grid = parse_input(puzzle_input);
+    let start = Point::origin();
     println!(
         "Grid: {} - {} {},{}",
-        grid.current(),
+        start,
         grid.bounds(),
         grid.width(),
         grid.height()
     );
 
     let goal = Point::from((grid.width() - 1, grid.height() - 1));
-    let path = breadth_first_search(&mut grid, goal)?;
+    let path = breadth_first_search(&grid, start, goal)?;
 
-    let _ = dijkstra_search(&mut grid, goal)?;
+    let (_heat, cheapest) = dijkstra_search(&grid, BucketQueue::new(), start, goal)?;
+    display_grid(&grid, &cheapest);
+    if let Some(file) = pgm {
+        write_pgm(&grid, &cheapest, file)?;
+    }
+    if benchmarks {
+        benchmark(&grid, start, goal)?;
+    }
     println!(
         "Part One: {} -- {}",
         path.len(),
@@ -140,8 +196,17 @@ fn part_one(puzzle_input: &str) -> Result<()> {
     Ok(())
 }
 fn main() -> Result<()> {
+    let args = std::env::args().collect::<Vec<String>>();
     let puzzle_input = std::fs::read_to_string("./puzzle_input.txt")?;
-    part_one(&puzzle_input)?;
+    let pgm = match args.iter().position(|arg| arg == "--pgm") {
+        Some(idx) => Some(
+            args.get(idx + 1)
+                .ok_or_else(|| anyhow!("--pgm needs a path"))?,
+        ),
+        None => None,
+    };
+    let benchmarks = args.iter().any(|arg| arg == "--benchmark");
+    part_one(&puzzle_input, pgm, benchmarks)?;
     Ok(())
 }
 
@@ -150,7 +215,19 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_it() {
-        println!("This is a test");
+    fn test_extract_path() {
+        let start = Point::origin();
+        let middle = Point::new(1, 0);
+        let goal = Point::new(1, 1);
+        let came_from = HashMap::from([(start, None), (middle, Some(start)), (goal, Some(middle))]);
+        assert_eq!(extract_path(&came_from, goal), vec![middle, start]);
+    }
+
+    #[test]
+    fn test_breadth_first_search() {
+        let grid = parse_input(include_str!("../puzzle_input.txt"));
+        let goal = Point::from((grid.width() - 1, grid.height() - 1));
+        let path = breadth_first_search(&grid, Point::origin(), goal).unwrap();
+        assert_eq!(path.len(), 12);
     }
 }
@@ -1,97 +1,108 @@
-use anyhow::{anyhow, Result};
-use regex::Regex;
-use std::cmp::max;
+use anyhow::Result;
+use std::collections::BTreeMap;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+use utils::AocParse;
 
-#[derive(Debug)]
+/// Some cubes of one color, like `3 blue`
+#[derive(Debug, AocParse)]
+#[aoc("{count} {color}")]
+struct Cubes {
+    count: usize,
+    color: String,
+}
+
+/// Counts of cubes by color.  Any color name works; a color that isn't in
+/// the hand has a count of 0.
+#[derive(Debug, Default, Clone, PartialEq)]
 struct GameHand {
-    red: usize,
-    blue: usize,
-    green: usize,
+    cubes: BTreeMap<String, usize>,
 }
+
 impl GameHand {
     pub fn new() -> Self {
-        Self {
-            red: 0,
-            blue: 0,
-            green: 0,
+        Self::default()
+    }
+
+    pub fn get(&self, color: &str) -> usize {
+        self.cubes.get(color).copied().unwrap_or(0)
+    }
+
+    pub fn set(&mut self, color: &str, count: usize) {
+        self.cubes.insert(color.to_string(), count);
+    }
+
+    /// The colors and counts, by color name
+    pub fn iter(&self) -> impl Iterator<Item = (&str, usize)> {
+        self.cubes
+            .iter()
+            .map(|(color, count)| (color.as_str(), *count))
+    }
+
+    /// The larger count of each color in either hand
+    pub fn merge(&self, other: &GameHand) -> GameHand {
+        let mut result = self.clone();
+        for (color, count) in other.iter() {
+            if count > result.get(color) {
+                result.set(color, count);
+            }
         }
+        result
+    }
+
+    /// True if every color in `self` fits in `other`
+    pub fn fits_in(&self, other: &GameHand) -> bool {
+        self.iter().all(|(color, count)| count <= other.get(color))
     }
 }
 
 impl std::fmt::Display for GameHand {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        let mut result: Vec<String> = Vec::new();
-        if self.red > 0 {
-            result.push(format!("{} red", self.red))
-        }
-        if self.blue > 0 {
-            result.push(format!("{} blue", self.blue))
-        }
-        if self.green > 0 {
-            result.push(format!("{} green", self.green))
-        }
+        let result = self
+            .iter()
+            .filter(|(_, count)| *count > 0)
+            .map(|(color, count)| format!("{} {}", count, color))
+            .collect::<Vec<String>>();
         write!(f, "{}", result.join(", "))
     }
 }
 
-impl TryFrom<&str> for GameHand {
-    type Error = anyhow::Error;
-    fn try_from(value: &str) -> Result<Self, Self::Error> {
+impl std::str::FromStr for GameHand {
+    type Err = anyhow::Error;
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
         let mut hand = Self::new();
-        for colors in value.split(',') {
-            let re = Regex::new(r"(?<count>\d+) (?<color>red|blue|green)").unwrap();
-            let Some(caps) = re.captures(colors) else {
-                return Err(anyhow!("Malformed colors"));
-            };
-
-            let count = caps["count"].parse::<usize>()?;
-            match &caps["color"] {
-                "red" => hand.red = count,
-                "blue" => hand.blue = count,
-                "green" => hand.green = count,
-                _ => return Err(anyhow!("Unknown color:v {}", &caps["color"])),
-            }
+        for cubes in value.split(',') {
+            let cubes = cubes.parse::<Cubes>()?;
+            hand.set(&cubes.color, cubes.count);
         }
-
         Ok(hand)
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, AocParse)]
+#[aoc("Game {count}: {hands: sep(;)}")]
 struct Game {
     pub count: usize,
     pub hands: Vec<GameHand>,
 }
 impl Game {
-    pub fn new() -> Self {
-        Self {
-            count: 0,
-            hands: Vec::new(),
-        }
-    }
+    /// True if every hand could have been drawn from `compare`
     pub fn possible(&self, compare: &GameHand) -> bool {
-        for hand in &self.hands {
-            if hand.red > compare.red || hand.blue > compare.blue || hand.green > compare.green {
-                return false;
-            }
-        }
-        true
+        self.hands.iter().all(|hand| hand.fits_in(compare))
     }
 
+    /// The fewest cubes of each color that the game could be played with
     pub fn min_cubes(&self) -> GameHand {
-        let mut red = 0;
-        let mut blue = 0;
-        let mut green = 0;
-        for hand in &self.hands {
-            red = max(red, hand.red);
-            blue = max(blue, hand.blue);
-            green = max(green, hand.green);
-        }
-        GameHand { red, blue, green }
+        self.hands
+            .iter()
+            .fold(GameHand::new(), |result, hand| result.merge(hand))
     }
-    pub fn power(&self) -> usize {
-        let mins = self.min_cubes();
-        mins.red * mins.blue * mins.green
+
+    /// Product of [Game::min_cubes] over the colors in `bag`.  A color the
+    /// game never shows needs 0 cubes, so the power is 0.
+    pub fn power(&self, bag: &GameHand) -> usize {
+        let min = self.min_cubes();
+        bag.iter().map(|(color, _)| min.get(color)).product()
     }
 }
 
@@ -106,72 +117,99 @@ impl std::fmt::Display for Game {
     }
 }
 
-impl TryFrom<&str> for Game {
-    type Error = anyhow::Error;
-    fn try_from(line: &str) -> Result<Self, Self::Error> {
-        // Break the line into the game counter and the hands
-        let meta_parts = line.split(':').collect::<Vec<&str>>();
-        if meta_parts.len() != 2 {
-            return Err(anyhow!("Game is malformed"));
-        }
-        // Get the game counter:
-        let counter_re = Regex::new(r"Game (?<game_counter>\d+)").unwrap();
-        let caps = counter_re
-            .captures(meta_parts[0])
-            .ok_or(anyhow!("Failed to parse game counter"))?;
-        let game_counter = caps["game_counter"].parse::<usize>()?;
-
-        let mut result = Self::new();
-        result.count = game_counter;
-
-        // Parse the hands
-        let hands = meta_parts[1].split(';').collect::<Vec<&str>>();
-        for hand in hands {
-            result.hands.push(GameHand::try_from(hand)?);
-        }
+/// Questions about a set of games and the bags they could be played with
+struct Games {
+    games: Vec<Game>,
+}
+
+impl Games {
+    /// The games that could have been played with `bag`
+    pub fn possible<'a>(&'a self, bag: &'a GameHand) -> impl Iterator<Item = &'a Game> {
+        self.games.iter().filter(|game| game.possible(bag))
+    }
+
+    /// The smallest bag that makes every one of `games` possible
+    pub fn smallest_bag<'a>(games: impl IntoIterator<Item = &'a Game>) -> GameHand {
+        games
+            .into_iter()
+            .fold(GameHand::new(), |bag, game| bag.merge(&game.min_cubes()))
+    }
+
+    /// The number of possible games for each limit of `color` in `limits`,
+    /// with the other colors limited by `bag`.
+    pub fn sweep(
+        &self,
+        bag: &GameHand,
+        color: &str,
+        limits: RangeInclusive<usize>,
+    ) -> Vec<(usize, usize)> {
+        // Each game needs at least this many of `color`, if the other colors fit
+        let mut needs = self
+            .games
+            .iter()
+            .map(|game| game.min_cubes())
+            .filter(|min| {
+                min.iter()
+                    .all(|(c, count)| c == color || count <= bag.get(c))
+            })
+            .map(|min| min.get(color))
+            .collect::<Vec<usize>>();
+        needs.sort();
 
-        Ok(result)
+        limits
+            .map(|limit| (limit, needs.partition_point(|need| *need <= limit)))
+            .collect()
     }
 }
 
-fn part_one(games: &[Game]) -> Result<()> {
+fn part_one(games: &Games, bag: &GameHand) -> Result<()> {
     let timer = std::time::Instant::now();
-    // Compare to
-    let compare_to = GameHand::try_from("12 red, 13 green, 14 blue").expect("Failed bad!!");
-
-    let total = games.iter().fold(0, |acc, game| {
-        if game.possible(&compare_to) {
-            acc + game.count
-        } else {
-            acc
-        }
-    });
+
+    let total = games.possible(bag).map(|game| game.count).sum::<usize>();
     println!("Part One: {} -- {:?}", total, timer.elapsed());
     Ok(())
 }
 
-fn part_two(games: &[Game]) -> Result<()> {
+fn part_two(games: &Games, bag: &GameHand) -> Result<()> {
     let timer = std::time::Instant::now();
 
-    let result = games.iter().fold(0, |acc, game| acc + game.power());
+    let result = games
+        .games
+        .iter()
+        .map(|game| game.power(bag))
+        .sum::<usize>();
     println!("Part Two: {} -- {:?}", result, timer.elapsed());
     Ok(())
 }
 
-use std::io::Write;
+/// Run with `--bag "12 red, 13 green, 14 blue"` to use a different bag for
+/// part one, and `--sweep red` to count the possible games as the limit for
+/// one color changes.
 fn main() -> Result<()> {
-    let lines = include_str!("../puzzle_input.txt")
-        .split('\n')
-        .collect::<Vec<&str>>();
-    let mut games: Vec<Game> = Vec::new();
-    print!("Parsing games...");
-    let _ = std::io::stdout().flush();
-    for line in lines {
-        games.push(Game::try_from(line)?);
-    }
-
-    part_one(&games)?;
-    part_two(&games)?;
+    let args = std::env::args().collect::<Vec<String>>();
+    let arg = |name: &str| {
+        args.iter()
+            .position(|arg| arg == name)
+            .and_then(|idx| args.get(idx + 1))
+    };
+    let bag = GameHand::from_str(arg("--bag").map_or("12 red, 13 green, 14 blue", |s| s))?;
+
+    let games = Games {
+        games: include_str!("../puzzle_input.txt")
+            .lines()
+            .map(str::parse)
+            .collect::<Result<Vec<Game>>>()?,
+    };
+
+    part_one(&games, &bag)?;
+    part_two(&games, &bag)?;
+
+    if let Some(color) = arg("--sweep") {
+        let most = Games::smallest_bag(&games.games).get(color);
+        for (limit, count) in games.sweep(&bag, color, 0..=most) {
+            println!("{:>3} {}: {} games", limit, color, count);
+        }
+    }
     Ok(())
 }
 
@@ -181,14 +219,68 @@ mod tests {
 
     #[test]
     fn test_single_hand() {
-        let game_hand = GameHand::try_from("8 green, 60 blue, 2 red").expect("oops");
-        println!("{}", &game_hand);
+        let game_hand = GameHand::from_str("8 green, 60 blue, 2 red").expect("oops");
+        assert_eq!(game_hand.get("blue"), 60);
+        assert_eq!(game_hand.get("purple"), 0);
+        assert_eq!(game_hand.to_string(), "60 blue, 8 green, 2 red");
+        assert!(GameHand::from_str("8 green, blue").is_err());
     }
 
     #[test]
     fn test_game() {
         let hands = " Game 12: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red ";
-        let game = Game::try_from(hands).expect("failed");
-        println!("{}", &game.min_cubes());
+        let game = Game::from_str(hands).expect("failed");
+        assert_eq!(game.min_cubes().to_string(), "6 blue, 13 green, 20 red");
+        let bag = GameHand::from_str("12 red, 13 green, 14 blue").unwrap();
+        assert_eq!(game.power(&bag), 6 * 13 * 20);
+    }
+
+    #[test]
+    fn test_missing_color() {
+        let game = Game::from_str("Game 1: 3 red, 2 green; 1 red").expect("failed");
+        let bag = GameHand::from_str("12 red, 13 green, 14 blue").unwrap();
+        assert_eq!(game.power(&bag), 0);
+    }
+
+    #[test]
+    fn test_any_colors() {
+        let game = Game::from_str("Game 1: 2 cyan, 4 red; 3 magenta, 2 cyan").expect("failed");
+        let bag = GameHand::from_str("2 cyan, 3 magenta, 4 red").unwrap();
+        assert_eq!(game.power(&bag), 2 * 4 * 3);
+        assert!(game.possible(&bag));
+        let bag = GameHand::from_str("2 cyan, 3 magenta").unwrap();
+        assert!(!game.possible(&bag));
+    }
+
+    #[test]
+    fn test_queries() {
+        let input = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
+        let games = Games {
+            games: input.lines().map(|line| line.parse().unwrap()).collect(),
+        };
+        let bag = GameHand::from_str("12 red, 13 green, 14 blue").unwrap();
+        let possible = games
+            .possible(&bag)
+            .map(|game| game.count)
+            .collect::<Vec<_>>();
+        assert_eq!(possible, vec![1, 2, 5]);
+
+        let smallest = Games::smallest_bag(&games.games);
+        assert_eq!(smallest.to_string(), "15 blue, 13 green, 20 red");
+        assert_eq!(games.possible(&smallest).count(), 5);
+        let smallest = Games::smallest_bag(games.possible(&bag));
+        assert_eq!(smallest.to_string(), "6 blue, 3 green, 6 red");
+
+        // Games 3 and 4 need more red than the bag has, whatever the blue limit
+        let sweep = games.sweep(&bag, "blue", 5..=15);
+        assert_eq!(sweep.first(), Some(&(5, 2)));
+        assert_eq!(sweep[1], (6, 3));
+        assert_eq!(sweep.last(), Some(&(15, 3)));
+        let sweep = games.sweep(&bag, "red", 19..=20);
+        assert_eq!(sweep, vec![(19, 3), (20, 4)]);
     }
 }
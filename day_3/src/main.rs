@@ -1,67 +1,33 @@
-/// Create an inclusive range that is row +/- 1, handling negatives.
-/// Example: r
-/// for row == 0: 0..=1
-/// for row == 1: 0..=2
-/// for row == 2: 1..3
+//! Gear Ratios
+//! The schematic is loaded into a [Grid] where each cell points at the
+//! number or symbol covering it.  From that, the graph of which parts touch
+//! which symbols is built once, so every adjacency query is a lookup.
 use anyhow::Result;
+use render::{Pairs, Render, Style};
+use utils::{Grid, Point};
 
-fn usize_range(row: usize) -> std::ops::RangeInclusive<usize> {
-    let lower = max(row as isize - 1, 0) as usize;
-    lower..=row + 1
-}
-
-#[derive(Debug)]
-struct Point {
-    pub x: usize,
-    pub y: usize,
-}
-
-impl Point {
-    pub fn new(x: usize, y: usize) -> Self {
-        Self { x, y }
-    }
-
-    pub fn within_bounds(&self, x1: usize, y1: usize, x2: usize, y2: usize) -> bool {
-        (x1..=x2).contains(&self.x) && (y1..=y2).contains(&self.y)
-        //self.x >= x1 && self.x <= x2 && self.y >= y1 && self.y <= y2
-    }
-
-    /// Generate an inclusive range of self.y +/- 1
-    /// This is used to filter the Vec of symbols and numbers later
-    pub fn y_range(&self) -> RangeInclusive<usize> {
-        usize_range(self.y)
-    }
-}
+mod render;
 
 #[derive(Debug)]
 struct Number {
     pub value: usize,
-    pub start: Point,
-    pub end: Point,
+    /// The digits as written, leading zeros and all
+    pub text: String,
+    pub start: Point<usize>,
+    pub end: Point<usize>,
 }
 
-use std::{cmp::max, ops::RangeInclusive};
-
 impl Number {
     /// Effectively returns the number of digits in this number.
     fn len(&self) -> usize {
         self.end.x - self.start.x + 1
     }
-    /// Given a space around a number, see if the point
-    /// is in the box
-    fn adjacent(&self, point: &Point) -> bool {
-        let x1 = max(self.start.x as isize - 1, 0) as usize;
-        let y1 = max(self.start.y as isize - 1, 0) as usize;
-        let x2 = self.end.x + 1;
-        let y2 = self.end.y + 1;
-        point.within_bounds(x1, y1, x2, y2)
-    }
 }
 
 #[derive(Debug)]
 struct Symbol {
     pub value: char,
-    pub location: Point,
+    pub location: Point<usize>,
 }
 
 impl Symbol {
@@ -70,121 +36,183 @@ impl Symbol {
     }
 }
 
+/// What covers a cell of the schematic.  The ids index
+/// [Schematics::numbers] and [Schematics::symbols].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Cell {
+    Empty,
+    Number(usize),
+    Symbol(usize),
+}
+
+/// The bipartite graph between numbers and the symbols they touch.
+#[derive(Debug, Default)]
+struct PartGraph {
+    /// For each number id, the ids of the symbols it touches
+    symbols_of: Vec<Vec<usize>>,
+    /// For each symbol id, the ids of the numbers touching it
+    parts_of: Vec<Vec<usize>>,
+}
+
+impl PartGraph {
+    /// Built from the symbols' side: each symbol checks the eight cells
+    /// around it for numbers
+    fn new(grid: &Grid<usize, Cell>, symbols: &[Symbol], number_count: usize) -> Self {
+        let mut symbols_of = vec![Vec::new(); number_count];
+        let mut parts_of = Vec::with_capacity(symbols.len());
+        for (id, symbol) in symbols.iter().enumerate() {
+            let mut parts = grid
+                .moore_neighbors(&symbol.location)
+                .filter_map(|point| match grid.get(&point) {
+                    Ok(Cell::Number(number)) => Some(*number),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            parts.sort_unstable();
+            parts.dedup();
+            for &number in &parts {
+                symbols_of[number].push(id);
+            }
+            parts_of.push(parts);
+        }
+        Self {
+            symbols_of,
+            parts_of,
+        }
+    }
+
+    /// Ids of the symbols touching the number `number`
+    pub fn symbols_touching(&self, number: usize) -> &[usize] {
+        &self.symbols_of[number]
+    }
+
+    /// Ids of the numbers touching the symbol `symbol`
+    pub fn parts_touching(&self, symbol: usize) -> &[usize] {
+        &self.parts_of[symbol]
+    }
+
+    /// Every (number id, symbol id) edge, by number
+    pub fn edges(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.symbols_of
+            .iter()
+            .enumerate()
+            .flat_map(|(number, symbols)| symbols.iter().map(move |&symbol| (number, symbol)))
+    }
+}
+
 #[derive(Debug)]
 struct Schematics {
     numbers: Vec<Number>,
     symbols: Vec<Symbol>,
+    grid: Grid<usize, Cell>,
+    graph: PartGraph,
 }
 
 impl Schematics {
-    pub fn new() -> Self {
+    pub fn new(numbers: Vec<Number>, symbols: Vec<Symbol>, grid: Grid<usize, Cell>) -> Self {
+        let graph = PartGraph::new(&grid, &symbols, numbers.len());
         Self {
-            numbers: Vec::new(),
-            symbols: Vec::new(),
+            numbers,
+            symbols,
+            grid,
+            graph,
         }
     }
 
-    pub fn push_number(&mut self, number: Number) {
-        self.numbers.push(number);
+    pub fn graph(&self) -> &PartGraph {
+        &self.graph
     }
-    pub fn push_symbol(&mut self, symbol: Symbol) {
-        self.symbols.push(symbol);
+
+    /// What covers the cell at `point`, if it is on the schematic
+    pub fn cell(&self, point: &Point<usize>) -> Option<Cell> {
+        self.grid.get_at(point.x, point.y).ok().copied()
     }
 
-    pub fn symbol_adjacent(&self, number: &Number) -> bool {
-        for symbol in self
-            .symbols
-            .iter()
-            .filter(|s| number.start.y_range().contains(&s.location.y))
-        {
-            if number.adjacent(&symbol.location) {
-                return true;
-            }
-        }
-        false
+    /// A number is a part if it touches any symbol
+    pub fn is_part(&self, number: usize) -> bool {
+        !self.graph.symbols_touching(number).is_empty()
     }
 
     fn find_parts(&self) -> Vec<usize> {
-        let mut result: Vec<usize> = Vec::new();
+        self.numbers
+            .iter()
+            .enumerate()
+            .filter(|(id, _)| self.is_part(*id))
+            .map(|(_, number)| number.value)
+            .collect()
+    }
 
-        for number in &self.numbers {
-            if self.symbol_adjacent(number) {
-                result.push(number.value);
-            }
-        }
-        result
+    /// A gear is a `*` touching exactly two parts
+    pub fn is_gear(&self, symbol: usize) -> bool {
+        self.symbols[symbol].is_gear() && self.graph.parts_touching(symbol).len() == 2
     }
 
     /// See if 2 numbers touch a gear
     fn find_gears(&self) -> Result<Vec<Vec<usize>>> {
-        let mut number_pairs: Vec<Vec<usize>> = Vec::new();
-        for symbol in self.symbols.iter().filter(|s| s.is_gear()) {
-            let mut numbers: Vec<usize> = Vec::new();
-            for number in self
-                .numbers
-                .iter()
-                .filter(|number| symbol.location.y_range().contains(&number.start.y))
-            {
-                if number.adjacent(&symbol.location) {
-                    numbers.push(number.value)
-                }
-            }
-            if numbers.len() == 2 {
-                number_pairs.push(numbers);
-            }
-        }
+        let number_pairs = (0..self.symbols.len())
+            .filter(|&id| self.is_gear(id))
+            .map(|id| self.graph.parts_touching(id))
+            .map(|parts| parts.iter().map(|&id| self.numbers[id].value).collect())
+            .collect();
         Ok(number_pairs)
     }
 }
 
-fn read_number(data: &[u8], cursor: usize, x: usize, y: usize) -> Result<Number> {
-    // Read until the end of the number
-    let start = Point::new(x, y);
-    let mut look_ahead = cursor;
-    while data[look_ahead].is_ascii_digit() {
-        look_ahead += 1;
+fn read_number(chars: &Grid<usize, char>, x: usize, y: usize) -> Result<Number> {
+    // Read until the end of the number, or the row
+    let mut digits = String::new();
+    while let Ok(c) = chars.get_at(x + digits.len(), y) {
+        if !c.is_ascii_digit() {
+            break;
+        }
+        digits.push(*c);
     }
-    let end = Point::new(look_ahead - cursor + start.x - 1, y);
-    let value = std::str::from_utf8(&data[cursor..look_ahead])?.parse::<usize>()?;
-    Ok(Number { value, start, end })
+    let start = Point::new(x, y);
+    let end = Point::new(x + digits.len() - 1, y);
+    let value = digits.parse::<usize>()?;
+    Ok(Number {
+        value,
+        text: digits,
+        start,
+        end,
+    })
 }
 
-fn read_schematic(data: &[u8]) -> Result<Schematics> {
-    let mut schematics = Schematics::new();
-
-    let mut x: usize = 0;
-    let mut y: usize = 0;
-    let mut cursor = 0;
-    while cursor < data.len() {
-        if data[cursor] == b'\n' {
-            y += 1;
-            x = 0;
-            cursor += 1;
-            continue;
-        }
+fn read_schematic(input: &str) -> Result<Schematics> {
+    let chars = Grid::<usize, char>::parse_undelim_str(input, Ok)?;
+    let mut numbers: Vec<Number> = Vec::new();
+    let mut symbols: Vec<Symbol> = Vec::new();
+    let mut cells: Vec<Cell> = Vec::with_capacity(chars.cells.len());
 
-        if data[cursor] == b'.' {
+    for y in 0..chars.height() {
+        let mut x = 0;
+        while x < chars.width() {
+            let c = *chars.get_at(x, y)?;
+            if c.is_ascii_digit() {
+                let number = read_number(&chars, x, y)?;
+                cells.extend(std::iter::repeat_n(
+                    Cell::Number(numbers.len()),
+                    number.len(),
+                ));
+                x += number.len();
+                numbers.push(number);
+                continue;
+            }
+            if c == '.' {
+                cells.push(Cell::Empty);
+            } else {
+                // Looks like a symbol
+                cells.push(Cell::Symbol(symbols.len()));
+                symbols.push(Symbol {
+                    value: c,
+                    location: Point::new(x, y),
+                });
+            }
             x += 1;
-            cursor += 1;
-            continue;
         }
-
-        if data[cursor].is_ascii_digit() {
-            let number = read_number(data, cursor, x, y)?;
-            cursor += number.len();
-            x += number.len();
-            schematics.push_number(number);
-            continue;
-        }
-        // Looks like a symbol
-        let value = data[cursor] as char;
-        let location = Point::new(x, y);
-        let symbol = Symbol { value, location };
-        schematics.push_symbol(symbol);
-        x += 1;
-        cursor += 1;
-    }
-    Ok(schematics)
+    }
+    let grid = Grid::from_cells(cells, chars.width(), chars.height());
+    Ok(Schematics::new(numbers, symbols, grid))
 }
 
 fn part_one(schematics: &Schematics) -> Result<()> {
@@ -206,9 +234,21 @@ fn part_two(schematics: &Schematics) -> Result<()> {
     Ok(())
 }
 
+/// Run with `--render` to reprint the schematic in color, or
+/// `--render=markers` for plain text with markers.  `--pairs` prints every
+/// adjacent part and symbol.
 fn main() -> Result<()> {
-    let data = include_bytes!("../puzzle_input.txt");
-    let schematics = read_schematic(data)?;
+    let args = std::env::args().collect::<Vec<String>>();
+    let input = include_str!("../puzzle_input.txt");
+    let schematics = read_schematic(input)?;
+    for arg in &args[1..] {
+        match arg.as_str() {
+            "--render" => print!("{}", Render::new(&schematics, Style::Ansi)),
+            "--render=markers" => print!("{}", Render::new(&schematics, Style::Markers)),
+            "--pairs" => print!("{}", Pairs(&schematics)),
+            _ => return Err(anyhow::anyhow!("Unknown argument: {}", arg)),
+        }
+    }
     part_one(&schematics)?;
     part_two(&schematics)?;
     Ok(())
@@ -218,17 +258,46 @@ fn main() -> Result<()> {
 mod tests {
     use super::*;
 
+    const EXAMPLE: &str = "467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..";
+
     #[test]
     fn test_it() {
-        let data = "467$..114..".as_bytes();
-        let schematic = read_schematic(data).expect("Fail");
-        println!("{:?}", &schematic);
+        let schematic = read_schematic("467$..114..").expect("Fail");
+        assert_eq!(schematic.numbers.len(), 2);
+        assert_eq!(schematic.find_parts(), vec![467]);
+        assert_eq!(schematic.cell(&Point::new(2, 0)), Some(Cell::Number(0)));
+        assert_eq!(schematic.cell(&Point::new(3, 0)), Some(Cell::Symbol(0)));
+        assert_eq!(schematic.cell(&Point::new(4, 0)), Some(Cell::Empty));
+        assert_eq!(schematic.cell(&Point::new(11, 0)), None);
     }
 
     #[test]
-    fn test_row_range() {
-        assert_eq!(usize_range(0), 0..=1);
-        assert_eq!(usize_range(1), 0..=2);
-        assert_eq!(usize_range(2), 1..=3);
+    fn test_graph() {
+        let schematic = read_schematic(EXAMPLE).expect("Fail");
+        assert_eq!(schematic.find_parts().iter().sum::<usize>(), 4361);
+        let gears = schematic.find_gears().unwrap();
+        assert_eq!(gears.iter().map(|p| p[0] * p[1]).sum::<usize>(), 467835);
+
+        let graph = schematic.graph();
+        // The first `*` touches 467 and 35
+        assert_eq!(graph.parts_touching(0), &[0, 2]);
+        // 114 and 58 touch nothing
+        assert!(graph.symbols_touching(1).is_empty());
+        assert!(!schematic.is_part(5));
+        // 617 touches the second `*`
+        assert_eq!(graph.symbols_touching(4), &[2]);
+        assert_eq!(graph.edges().count(), 8);
+        assert!(graph
+            .edges()
+            .all(|(number, symbol)| graph.parts_touching(symbol).contains(&number)));
     }
 }
@@ -0,0 +1,189 @@
+//! Render
+//! Reprints a schematic showing what was counted: part numbers, numbers
+//! that touch no symbol, and gears.  [Style::Ansi] colors the text for a
+//! terminal.  [Style::Markers] keeps the text plain and prints a line of
+//! markers under each row:
+//!
+//! ```text
+//! 467..114..
+//! ppp  nnn
+//! ...*......
+//!    g
+//! ```
+//!
+//! [Pairs] lists every part and the symbol it touches, one per line, as
+//! comma separated values.
+use super::{Cell, Schematics};
+use utils::Point;
+
+const PART: &str = "\x1b[32m";
+const NON_PART: &str = "\x1b[31m";
+const GEAR: &str = "\x1b[1;33m";
+const RESET: &str = "\x1b[0m";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+    /// Green parts, red non-parts and bold yellow gears
+    Ansi,
+    /// `p` under parts, `n` under non-parts and `g` under gears
+    Markers,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Plain,
+    Part,
+    NonPart,
+    Gear,
+}
+
+impl Kind {
+    fn color(&self) -> &'static str {
+        match self {
+            Self::Plain => RESET,
+            Self::Part => PART,
+            Self::NonPart => NON_PART,
+            Self::Gear => GEAR,
+        }
+    }
+
+    fn marker(&self) -> char {
+        match self {
+            Self::Plain => ' ',
+            Self::Part => 'p',
+            Self::NonPart => 'n',
+            Self::Gear => 'g',
+        }
+    }
+}
+
+pub struct Render<'a> {
+    schematics: &'a Schematics,
+    style: Style,
+}
+
+impl<'a> Render<'a> {
+    pub fn new(schematics: &'a Schematics, style: Style) -> Self {
+        Self { schematics, style }
+    }
+
+    /// The text and kind of the cell at `x`, `y`
+    fn cell(&self, x: usize, y: usize) -> (char, Kind) {
+        let schematics = self.schematics;
+        match schematics.cell(&Point::new(x, y)) {
+            Some(Cell::Number(id)) => {
+                let number = &schematics.numbers[id];
+                let c = number.text.as_bytes()[x - number.start.x] as char;
+                match schematics.is_part(id) {
+                    true => (c, Kind::Part),
+                    false => (c, Kind::NonPart),
+                }
+            }
+            Some(Cell::Symbol(id)) => {
+                let c = schematics.symbols[id].value;
+                match schematics.is_gear(id) {
+                    true => (c, Kind::Gear),
+                    false => (c, Kind::Plain),
+                }
+            }
+            _ => ('.', Kind::Plain),
+        }
+    }
+}
+
+impl<'a> std::fmt::Display for Render<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let grid = &self.schematics.grid;
+        for y in 0..grid.height() {
+            let row = (0..grid.width())
+                .map(|x| self.cell(x, y))
+                .collect::<Vec<_>>();
+            match self.style {
+                Style::Ansi => {
+                    let mut current = Kind::Plain;
+                    for (c, kind) in row {
+                        if kind != current {
+                            write!(f, "{}", kind.color())?;
+                            current = kind;
+                        }
+                        write!(f, "{}", c)?;
+                    }
+                    if current != Kind::Plain {
+                        write!(f, "{}", RESET)?;
+                    }
+                    writeln!(f)?;
+                }
+                Style::Markers => {
+                    let text = row.iter().map(|(c, _)| *c).collect::<String>();
+                    let markers = row
+                        .iter()
+                        .map(|(_, kind)| kind.marker())
+                        .collect::<String>();
+                    writeln!(f, "{}", text)?;
+                    writeln!(f, "{}", markers.trim_end())?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Every adjacent part and symbol, as
+/// `part,part x,part y,symbol,symbol x,symbol y` with a header line.
+/// Positions are the 0 based column and row of the first digit and of the
+/// symbol.
+pub struct Pairs<'a>(pub &'a Schematics);
+
+impl<'a> std::fmt::Display for Pairs<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let schematics = self.0;
+        writeln!(f, "part,part_x,part_y,symbol,symbol_x,symbol_y")?;
+        for (number, symbol) in schematics.graph().edges() {
+            let number = &schematics.numbers[number];
+            let symbol = &schematics.symbols[symbol];
+            writeln!(
+                f,
+                "{},{},{},{},{},{}",
+                number.value,
+                number.start.x,
+                number.start.y,
+                symbol.value,
+                symbol.location.x,
+                symbol.location.y
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::read_schematic;
+
+    #[test]
+    fn test_render() {
+        let schematics = read_schematic("467..114..\n...*......\n..35..633.").unwrap();
+
+        let markers = Render::new(&schematics, Style::Markers).to_string();
+        assert_eq!(
+            markers,
+            "467..114..\nppp  nnn\n...*......\n   g\n..35..633.\n  pp  nnn\n"
+        );
+
+        let ansi = Render::new(&schematics, Style::Ansi).to_string();
+        let first = ansi.lines().next().unwrap();
+        assert_eq!(first, "\x1b[32m467\x1b[0m..\x1b[31m114\x1b[0m..");
+
+        let schematics = read_schematic("007*.").unwrap();
+        let markers = Render::new(&schematics, Style::Markers).to_string();
+        assert_eq!(markers, "007*.\nppp\n");
+
+        let schematics = read_schematic("467..114..\n...*......\n..35..633.").unwrap();
+        let pairs = Pairs(&schematics).to_string();
+        assert_eq!(
+            pairs,
+            "part,part_x,part_y,symbol,symbol_x,symbol_y\n467,0,0,*,3,1\n35,2,2,*,3,1\n"
+        );
+    }
+}
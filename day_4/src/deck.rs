@@ -0,0 +1,214 @@
+//! Deck
+//! The cards in order, and statistics about how they score and how they
+//! win copies of each other.  Card `n` wins one copy of each of the next
+//! `matches` cards, for every copy of card `n`.  Copies never run past the
+//! last card.
+//!
+//! Counts that could explode on a hostile deck use checked arithmetic and
+//! return an error instead of wrapping.
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow, Result};
+
+use super::Card;
+
+#[derive(Debug)]
+pub struct Deck {
+    pub cards: Vec<Card>,
+}
+
+/// The copies one instance of a card wins, and the copies those win, and so
+/// on.
+#[derive(Debug, PartialEq, Eq)]
+pub struct CopyTree {
+    pub card: usize,
+    pub children: Vec<CopyTree>,
+}
+
+impl std::fmt::Display for CopyTree {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fn write_node(
+            node: &CopyTree,
+            depth: usize,
+            f: &mut std::fmt::Formatter<'_>,
+        ) -> std::fmt::Result {
+            writeln!(f, "{:indent$}Card {}", "", node.card, indent = depth * 2)?;
+            node.children
+                .iter()
+                .try_for_each(|child| write_node(child, depth + 1, f))
+        }
+        write_node(self, 0, f)
+    }
+}
+
+impl Deck {
+    pub fn new(cards: Vec<Card>) -> Self {
+        Self { cards }
+    }
+
+    /// Index of the last card won by the card at `idx`, if it wins any
+    fn last_won(&self, idx: usize) -> Option<usize> {
+        let matches = self.cards[idx].matches();
+        match matches == 0 || idx == self.cards.len() - 1 {
+            true => None,
+            false => Some(std::cmp::min(idx + matches, self.cards.len() - 1)),
+        }
+    }
+
+    fn position(&self, card: usize) -> Result<usize> {
+        self.cards
+            .iter()
+            .position(|c| c.id == card)
+            .ok_or_else(|| anyhow!("No card {}", card))
+    }
+
+    /// Points for each card, in order
+    pub fn points(&self) -> Vec<usize> {
+        self.cards.iter().map(Card::points).collect()
+    }
+
+    /// How many cards have each number of matches
+    pub fn match_histogram(&self) -> BTreeMap<usize, usize> {
+        let mut histogram = BTreeMap::new();
+        for card in &self.cards {
+            *histogram.entry(card.matches()).or_default() += 1;
+        }
+        histogram
+    }
+
+    /// The number of copies of each card, originals included.  Rather than
+    /// adding copies card by card, the copies a card wins are added to a
+    /// running bonus that every following card receives, and taken off again
+    /// where its run ends, so this is linear in the number of cards.
+    pub fn copies(&self) -> Result<Vec<usize>> {
+        let overflow = |idx: usize| anyhow!("Card {}: too many copies", self.cards[idx].id);
+        let mut counts: Vec<usize> = Vec::with_capacity(self.cards.len());
+        // expiring[idx] is the part of the bonus that doesn't reach card idx
+        let mut expiring: Vec<usize> = vec![0; self.cards.len() + 1];
+        let mut bonus = 0usize;
+
+        for idx in 0..self.cards.len() {
+            bonus -= expiring[idx];
+            let copies = bonus.checked_add(1).ok_or_else(|| overflow(idx))?;
+            counts.push(copies);
+
+            if let Some(end) = self.last_won(idx) {
+                bonus = bonus.checked_add(copies).ok_or_else(|| overflow(idx))?;
+                expiring[end + 1] += copies;
+            }
+        }
+        Ok(counts)
+    }
+
+    /// For each card, the copies one instance of it produces: the cards it
+    /// wins, plus everything those produce.  Worked from the last card back,
+    /// with a suffix sum so each card is only visited once.
+    pub fn produced(&self) -> Result<Vec<usize>> {
+        let len = self.cards.len();
+        let mut produced = vec![0usize; len];
+        // suffix[idx] is the sum of (1 + produced) for cards idx..
+        let mut suffix = vec![0usize; len + 1];
+        for idx in (0..len).rev() {
+            if let Some(end) = self.last_won(idx) {
+                produced[idx] = suffix[idx + 1] - suffix[end + 1];
+            }
+            suffix[idx] = produced[idx]
+                .checked_add(1)
+                .and_then(|n| n.checked_add(suffix[idx + 1]))
+                .ok_or_else(|| anyhow!("Card {}: too many copies", self.cards[idx].id))?;
+        }
+        Ok(produced)
+    }
+
+    /// The `count` cards that produce the most copies, as (card, copies),
+    /// most first.  Ties are in card order.
+    pub fn top_producers(&self, count: usize) -> Result<Vec<(usize, usize)>> {
+        let mut produced = self
+            .cards
+            .iter()
+            .map(|card| card.id)
+            .zip(self.produced()?)
+            .collect::<Vec<_>>();
+        produced.sort_by_key(|&(_, copies)| std::cmp::Reverse(copies));
+        produced.truncate(count);
+        Ok(produced)
+    }
+
+    /// The copies one instance of `card` wins, down to `depth` levels.  The
+    /// full tree has [Deck::produced] nodes below the root, which can be
+    /// huge.
+    pub fn copy_tree(&self, card: usize, depth: usize) -> Result<CopyTree> {
+        let idx = self.position(card)?;
+        Ok(self.copy_tree_at(idx, depth))
+    }
+
+    fn copy_tree_at(&self, idx: usize, depth: usize) -> CopyTree {
+        let children = match (depth, self.last_won(idx)) {
+            (0, _) | (_, None) => Vec::new(),
+            (_, Some(end)) => (idx + 1..=end)
+                .map(|child| self.copy_tree_at(child, depth - 1))
+                .collect(),
+        };
+        CopyTree {
+            card: self.cards[idx].id,
+            children,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_data;
+
+    const EXAMPLE: &str = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
+
+    #[test]
+    fn test_stats() {
+        let deck = Deck::new(parse_data(EXAMPLE).unwrap());
+        assert_eq!(deck.points(), vec![8, 2, 2, 1, 0, 0]);
+        assert_eq!(deck.copies().unwrap(), vec![1, 2, 4, 8, 14, 1]);
+        assert_eq!(
+            deck.match_histogram(),
+            BTreeMap::from([(0, 2), (1, 1), (2, 2), (4, 1)])
+        );
+        assert_eq!(deck.produced().unwrap(), vec![14, 6, 3, 1, 0, 0]);
+        assert_eq!(deck.top_producers(2).unwrap(), vec![(1, 14), (2, 6)]);
+
+        let tree = deck.copy_tree(3, 5).unwrap();
+        assert_eq!(tree.to_string(), "Card 3\n  Card 4\n    Card 5\n  Card 5\n");
+        let tree = deck.copy_tree(1, 1).unwrap();
+        assert_eq!(tree.children.len(), 4);
+        assert!(tree.children.iter().all(|child| child.children.is_empty()));
+        assert!(deck.copy_tree(7, 1).is_err());
+    }
+
+    #[test]
+    fn test_overflow() {
+        // Every card wins a copy of every following card, so card n has
+        // 2^(n-1) copies
+        let deck = |count: usize| {
+            Deck::new(
+                (1..=count)
+                    .map(|id| Card {
+                        id,
+                        winners: [1].into(),
+                        haves: vec![1; 70],
+                    })
+                    .collect(),
+            )
+        };
+        let counts = deck(10).copies().unwrap();
+        assert_eq!(counts[9], 512);
+        let deck = deck(70);
+        let err = deck.copies().unwrap_err();
+        assert_eq!(err.to_string(), "Card 65: too many copies");
+        let err = deck.produced().unwrap_err();
+        assert_eq!(err.to_string(), "Card 6: too many copies");
+    }
+}
@@ -1,28 +1,25 @@
+use std::collections::HashSet;
+
 use anyhow::{anyhow, Result};
-#[derive(Debug)]
+use deck::Deck;
+use utils::AocParse;
+
+mod deck;
+
+#[derive(Debug, AocParse)]
+#[aoc("Card {id}: {winners: ws} | {haves: ws}")]
 struct Card {
-    winners: Vec<usize>,
+    id: usize,
+    winners: HashSet<usize>,
     haves: Vec<usize>,
 }
 
 impl Card {
     pub fn matches(&self) -> usize {
-        /*
-        let mut matches = 0;
-        for have in &self.haves {
-            if self.winners.contains(have) {
-                matches += 1;
-            }
-        }
-        matches
-        */
-        self.haves.iter().fold(0, |acc, have| {
-            if self.winners.contains(have) {
-                acc + 1
-            } else {
-                acc
-            }
-        })
+        self.haves
+            .iter()
+            .filter(|have| self.winners.contains(have))
+            .count()
     }
 
     pub fn points(&self) -> usize {
@@ -35,87 +32,71 @@ impl Card {
     }
 }
 
-impl TryFrom<&str> for Card {
-    type Error = anyhow::Error;
-
-    fn try_from(value: &str) -> Result<Self, Self::Error> {
-        let card_parts = value.split(':').collect::<Vec<&str>>();
-        if card_parts.len() != 2 {
-            return Err(anyhow!("Malformed card"));
-        }
-        let game_parts = card_parts[1].split('|').collect::<Vec<&str>>();
-        if game_parts.len() != 2 {
-            return Err(anyhow!("Malformed game parts"));
-        }
-
-        let winners = game_parts[0].split(' ').collect::<Vec<&str>>();
-
-        let winners = winners
-            .iter()
-            .filter(|s| !s.is_empty())
-            .map(|s| s.parse::<usize>())
-            .collect::<std::result::Result<Vec<usize>, _>>()?;
-
-        //    let winners = winners.map(|s| s.parse::<usize>().map_err(|e| e.to_string())?)
-        let haves = game_parts[1]
-            .split(' ')
-            .filter(|s| !s.is_empty())
-            .map(|s| s.parse::<usize>())
-            .collect::<std::result::Result<Vec<usize>, _>>()?;
-
-        Ok(Card { winners, haves })
-    }
-}
-
-fn part_one(cards: &[Card]) -> Result<()> {
+fn part_one(deck: &Deck) -> Result<()> {
     let timer = std::time::Instant::now();
 
-    let total = cards.iter().fold(0, |acc, card| acc + card.points());
+    let total: usize = deck.points().iter().sum();
 
     println!("Part One: {} -- {:?}", total, timer.elapsed());
     Ok(())
 }
 
-fn part_two(cards: &Vec<Card>) -> Result<()> {
+fn part_two(deck: &Deck) -> Result<()> {
     let timer = std::time::Instant::now();
 
-    let mut card_counts: Vec<usize> = vec![1; cards.len()];
-    let mut total = 0usize;
-
-    for (idx, card) in cards.iter().enumerate() {
-        total += card_counts[idx];
+    let total = deck
+        .copies()?
+        .iter()
+        .try_fold(0usize, |acc, &count| acc.checked_add(count))
+        .ok_or_else(|| anyhow!("Too many cards"))?;
 
-        let matches = card.matches();
-        if matches == 0 || idx == cards.len() - 1 {
-            continue;
-        }
+    println!("Part Two: {} -- {:?}", &total, timer.elapsed());
+    Ok(())
+}
 
-        let cnt = std::cmp::min(idx + matches, cards.len() - 1);
-        for _ in 0..card_counts[idx] {
-            for i in card_counts.iter_mut().take(cnt + 1).skip(idx + 1) {
-                *i += 1;
-            }
-        }
+fn stats(deck: &Deck, tree: Option<usize>) -> Result<()> {
+    println!("Matches:");
+    for (matches, cards) in deck.match_histogram() {
+        println!("{:>4}: {}", matches, cards);
+    }
+    println!("Most copies produced:");
+    for (card, copies) in deck.top_producers(5)? {
+        println!("Card {:>4}: {}", card, copies);
+    }
+    if let Some(card) = tree {
+        print!("{}", deck.copy_tree(card, 3)?);
     }
-
-    println!("Part Two: {} -- {:?}", &total, timer.elapsed());
     Ok(())
 }
 
 fn parse_data(data: &str) -> Result<Vec<Card>> {
     let cards = data
         .split('\n')
-        .map(Card::try_from)
+        .map(str::parse)
         .collect::<Result<Vec<Card>, _>>()?;
 
     Ok(cards)
 }
 
+/// Run with `--stats` for statistics about the deck, and add `--tree <card>`
+/// to see the first levels of that card's copy tree.
 fn main() -> Result<()> {
+    let args = std::env::args().collect::<Vec<String>>();
     let data = include_str!("../puzzle_input.txt");
-    let cards = parse_data(data.trim())?;
-
-    part_one(&cards)?;
-    part_two(&cards)?;
+    let deck = Deck::new(parse_data(data.trim())?);
+
+    part_one(&deck)?;
+    part_two(&deck)?;
+    if args.iter().any(|arg| arg == "--stats") {
+        let tree = match args.iter().position(|arg| arg == "--tree") {
+            Some(idx) => Some(
+                args.get(idx + 1)
+                    .ok_or_else(|| anyhow!("--tree needs a card number"))?
+                    .parse()?,
+            ),
+            None => None,
+        };
+        stats(&deck, tree)?;
+    }
     Ok(())
 }
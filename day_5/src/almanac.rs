@@ -0,0 +1,770 @@
+//! Almanac
+//! Each `X-to-Y map` in the almanac is a piecewise linear function: values
+//! in a source range move by a fixed amount, and every other value maps to
+//! itself.  Composing two such functions gives another one, so the whole
+//! chain from `seed` to `location` folds into a single [Mapping], and both
+//! puzzle queries are answered from that.
+//!
+//! Within a piece, the function is increasing, so the smallest image of a
+//! range is the image of the start of one of its pieces.
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::ops::Range;
+
+use anyhow::{anyhow, Result};
+use utils::{numbers, sections, AocParse};
+
+/// One line of a map: `length` values from `source` move to `destination`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, AocParse)]
+#[aoc("{destination} {source} {length}")]
+pub struct MapEntry {
+    pub destination: u64,
+    pub source: u64,
+    pub length: u64,
+}
+
+/// Values in `source` map to `destination` onwards
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Piece {
+    pub source: Range<u64>,
+    pub destination: u64,
+}
+
+impl Piece {
+    fn identity(source: Range<u64>) -> Self {
+        let destination = source.start;
+        Self {
+            source,
+            destination,
+        }
+    }
+
+    fn is_identity(&self) -> bool {
+        self.source.start == self.destination
+    }
+
+    pub fn len(&self) -> u64 {
+        self.source.end - self.source.start
+    }
+
+    /// `value` must be in `source`
+    pub fn get(&self, value: u64) -> u64 {
+        self.destination + (value - self.source.start)
+    }
+
+    /// The range `source` maps to
+    pub fn image(&self) -> Range<u64> {
+        self.destination..self.destination + self.len()
+    }
+}
+
+/// A piecewise linear function on `u64`.  The pieces are sorted, don't
+/// overlap and are never the identity; values between them map to
+/// themselves.  Pieces are half open, so no piece covers or reaches
+/// `u64::MAX`: it always maps to itself, and only from itself.  That is why
+/// walking `0..u64::MAX` covers every other value.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Mapping {
+    pieces: Vec<Piece>,
+}
+
+impl Mapping {
+    /// Build a mapping from the lines of a map.  Entries must not overlap,
+    /// and must not run past `u64::MAX`.
+    pub fn new(entries: impl IntoIterator<Item = MapEntry>) -> Result<Self> {
+        let mut pieces = entries
+            .into_iter()
+            .map(|entry| {
+                match (
+                    entry.source.checked_add(entry.length),
+                    entry.destination.checked_add(entry.length),
+                ) {
+                    (Some(end), Some(_)) => Ok(Piece {
+                        source: entry.source..end,
+                        destination: entry.destination,
+                    }),
+                    _ => Err(anyhow!(
+                        "Map entry out of range: {} {} {}",
+                        entry.destination,
+                        entry.source,
+                        entry.length
+                    )),
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+        pieces.sort_by_key(|piece| piece.source.start);
+        if let Some(pair) = pieces
+            .windows(2)
+            .find(|pair| pair[0].source.end > pair[1].source.start)
+        {
+            return Err(anyhow!(
+                "Map entries overlap: {:?} and {:?}",
+                pair[0].source,
+                pair[1].source
+            ));
+        }
+        Ok(Self::from_pieces(pieces))
+    }
+
+    /// Sorted, non-overlapping pieces.  Empty and identity pieces are
+    /// dropped, and pieces that continue each other are merged.
+    fn from_pieces(pieces: Vec<Piece>) -> Self {
+        let mut merged: Vec<Piece> = Vec::with_capacity(pieces.len());
+        for piece in pieces {
+            if piece.source.is_empty() || piece.is_identity() {
+                continue;
+            }
+            match merged.last_mut() {
+                Some(last)
+                    if last.source.end == piece.source.start
+                        && last.image().end == piece.destination =>
+                {
+                    last.source.end = piece.source.end
+                }
+                _ => merged.push(piece),
+            }
+        }
+        Self { pieces: merged }
+    }
+
+    pub fn get(&self, value: u64) -> u64 {
+        let idx = self
+            .pieces
+            .partition_point(|piece| piece.source.end <= value);
+        match self.pieces.get(idx) {
+            Some(piece) if piece.source.contains(&value) => piece.get(value),
+            _ => value,
+        }
+    }
+
+    /// Split `range` into pieces of this mapping, including identity pieces
+    /// for the gaps, in order.
+    pub fn segments(&self, range: Range<u64>) -> Vec<Piece> {
+        let mut segments = Vec::new();
+        let mut start = range.start;
+        let first = self
+            .pieces
+            .partition_point(|piece| piece.source.end <= start);
+        for piece in &self.pieces[first..] {
+            if piece.source.start >= range.end {
+                break;
+            }
+            if start < piece.source.start {
+                segments.push(Piece::identity(start..piece.source.start));
+                start = piece.source.start;
+            }
+            let end = std::cmp::min(piece.source.end, range.end);
+            segments.push(Piece {
+                source: start..end,
+                destination: piece.get(start),
+            });
+            start = end;
+        }
+        if start < range.end {
+            segments.push(Piece::identity(start..range.end));
+        }
+        segments
+    }
+
+    /// The smallest value `range` maps to
+    pub fn min_over(&self, range: Range<u64>) -> Option<u64> {
+        self.segments(range)
+            .iter()
+            .map(|piece| piece.destination)
+            .min()
+    }
+
+    /// Every value that maps into `range`, as sorted, merged ranges
+    pub fn preimage(&self, range: Range<u64>) -> Vec<Range<u64>> {
+        let sources = self
+            .segments(0..u64::MAX)
+            .into_iter()
+            .filter_map(|piece| {
+                let image = piece.image();
+                let start = std::cmp::max(image.start, range.start);
+                let end = std::cmp::min(image.end, range.end);
+                (start < end).then(|| {
+                    let source = piece.source.start + (start - piece.destination);
+                    source..source + (end - start)
+                })
+            })
+            .collect();
+        merge(sources)
+    }
+
+    /// This mapping, followed by `next`
+    pub fn then(&self, next: &Mapping) -> Mapping {
+        let pieces = self
+            .segments(0..u64::MAX)
+            .into_iter()
+            .flat_map(|piece| {
+                next.segments(piece.image()).into_iter().map(move |part| {
+                    let start = piece.source.start + (part.source.start - piece.destination);
+                    Piece {
+                        source: start..start + part.len(),
+                        destination: part.destination,
+                    }
+                })
+            })
+            .collect();
+        Self::from_pieces(pieces)
+    }
+}
+
+/// Sort `ranges`, and join any that overlap or touch
+fn merge(mut ranges: Vec<Range<u64>>) -> Vec<Range<u64>> {
+    ranges.sort_by_key(|range| range.start);
+    let mut merged: Vec<Range<u64>> = Vec::with_capacity(ranges.len());
+    for range in ranges.into_iter().filter(|range| !range.is_empty()) {
+        match merged.last_mut() {
+            Some(last) if last.end >= range.start => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+/// The values in both `a` and `b`, which must be sorted and merged
+fn intersect(a: &[Range<u64>], b: &[Range<u64>]) -> Vec<Range<u64>> {
+    let mut result = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        let start = std::cmp::max(a[i].start, b[j].start);
+        let end = std::cmp::min(a[i].end, b[j].end);
+        if start < end {
+            result.push(start..end);
+        }
+        match a[i].end < b[j].end {
+            true => i += 1,
+            false => j += 1,
+        }
+    }
+    result
+}
+
+/// An `X-to-Y map` section
+#[derive(Debug)]
+pub struct Stage {
+    pub source: String,
+    pub destination: String,
+    pub mapping: Mapping,
+}
+
+/// A stage applied to a value
+#[derive(Debug)]
+pub struct Step<'a> {
+    pub stage: &'a Stage,
+    pub input: u64,
+    pub output: u64,
+}
+
+impl<'a> Step<'a> {
+    pub fn changed(&self) -> bool {
+        self.input != self.output
+    }
+}
+
+impl<'a> std::fmt::Display for Step<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} -> {} {}",
+            self.stage.source, self.input, self.stage.destination, self.output
+        )?;
+        if self.changed() {
+            write!(f, " (changed)")?;
+        }
+        Ok(())
+    }
+}
+
+/// The categories are the nodes of a directed graph, and each stage is an
+/// edge from its source to its destination.  Any category can be resolved
+/// to any category it has a path to.  The graph must not have loops, or two
+/// stages between the same pair of categories.
+///
+/// Paths between two categories are free to disagree.  When there is more
+/// than one, the one with the fewest stages is used, and a tie goes to the
+/// path whose first differing stage comes first in the almanac.
+#[derive(Debug)]
+pub struct Almanac {
+    pub seeds: Vec<u64>,
+    pub stages: Vec<Stage>,
+    /// For each category, the stages that map from it
+    links: HashMap<String, Vec<usize>>,
+}
+
+impl Almanac {
+    pub fn new(seeds: Vec<u64>, stages: Vec<Stage>) -> Result<Self> {
+        let mut links: HashMap<String, Vec<usize>> = HashMap::new();
+        for (idx, stage) in stages.iter().enumerate() {
+            let from = links.entry(stage.source.clone()).or_default();
+            if from
+                .iter()
+                .any(|&other| stages[other].destination == stage.destination)
+            {
+                return Err(anyhow!(
+                    "More than one map from {} to {}",
+                    stage.source,
+                    stage.destination
+                ));
+            }
+            from.push(idx);
+        }
+        let almanac = Self {
+            seeds,
+            stages,
+            links,
+        };
+        if let Some(cycle) = almanac.find_cycle() {
+            return Err(anyhow!("Maps loop: {}", cycle.join(" -> ")));
+        }
+        Ok(almanac)
+    }
+
+    /// A `seeds:` line, then any number of `X-to-Y map:` sections
+    pub fn parse(input: &str) -> Result<Self> {
+        let mut sections = sections(input).with_headers();
+
+        let (seeds, _) = sections.next().ok_or(anyhow!("Empty almanac"))?;
+        let seeds = seeds
+            .strip_prefix("seeds:")
+            .ok_or(anyhow!("Expected seeds, found {}", seeds))?;
+        let seeds = numbers::<u64>(seeds).collect::<Result<Vec<u64>>>()?;
+
+        let mut stages = Vec::new();
+        for (header, body) in sections {
+            let (source, destination) = header
+                .strip_suffix(" map")
+                .and_then(|name| name.split_once("-to-"))
+                .ok_or(anyhow!("Unexpected map: {}", header))?;
+            let entries = body
+                .lines()
+                .map(|line| line.parse::<MapEntry>())
+                .collect::<Result<Vec<_>>>()
+                .and_then(Mapping::new)
+                .map_err(|e| anyhow!("{}: {}", header, e))?;
+            stages.push(Stage {
+                source: source.to_string(),
+                destination: destination.to_string(),
+                mapping: entries,
+            });
+        }
+        Self::new(seeds, stages)
+    }
+
+    fn is_category(&self, name: &str) -> bool {
+        self.stages
+            .iter()
+            .any(|stage| stage.source == name || stage.destination == name)
+    }
+
+    /// Stages leaving `category`
+    fn stages_from<'a>(&'a self, category: &str) -> impl Iterator<Item = usize> + 'a {
+        self.links.get(category).into_iter().flatten().copied()
+    }
+
+    /// A loop in the graph, as the categories around it with the first
+    /// repeated at the end
+    fn find_cycle(&self) -> Option<Vec<&str>> {
+        let mut done = HashSet::new();
+        self.stages
+            .iter()
+            .find_map(|stage| self.visit(&stage.source, &mut Vec::new(), &mut done))
+    }
+
+    /// Depth first search from `category`, with `path` the categories on the
+    /// way here and `done` the categories known not to lead to a loop
+    fn visit<'a>(
+        &'a self,
+        category: &'a str,
+        path: &mut Vec<&'a str>,
+        done: &mut HashSet<&'a str>,
+    ) -> Option<Vec<&'a str>> {
+        if let Some(pos) = path.iter().position(|&c| c == category) {
+            let mut cycle = path[pos..].to_vec();
+            cycle.push(category);
+            return Some(cycle);
+        }
+        if done.contains(category) {
+            return None;
+        }
+        path.push(category);
+        for idx in self.stages_from(category) {
+            if let Some(cycle) = self.visit(&self.stages[idx].destination, path, done) {
+                return Some(cycle);
+            }
+        }
+        path.pop();
+        done.insert(category);
+        None
+    }
+
+    /// The shortest run of stages from category `from` to category `to`.
+    /// Stages are tried in almanac order, so ties go to the earliest.
+    fn chain(&self, from: &str, to: &str) -> Result<Vec<&Stage>> {
+        for category in [from, to] {
+            if !self.is_category(category) {
+                return Err(anyhow!("No category {}", category));
+            }
+        }
+        // The stage that first reached each category
+        let mut reached_by: HashMap<&str, usize> = HashMap::new();
+        let mut queue = VecDeque::from([from]);
+        while let Some(category) = queue.pop_front() {
+            if category == to {
+                let mut chain = Vec::new();
+                let mut category = to;
+                while category != from {
+                    let stage = &self.stages[reached_by[category]];
+                    chain.push(stage);
+                    category = &stage.source;
+                }
+                chain.reverse();
+                return Ok(chain);
+            }
+            for idx in self.stages_from(category) {
+                let next = self.stages[idx].destination.as_str();
+                if next != from && !reached_by.contains_key(next) {
+                    reached_by.insert(next, idx);
+                    queue.push_back(next);
+                }
+            }
+        }
+        Err(anyhow!("No maps lead from {} to {}", from, to))
+    }
+
+    /// `value` of category `from` through each stage to category `to`
+    pub fn trace(&self, from: &str, to: &str, value: u64) -> Result<Vec<Step<'_>>> {
+        let mut value = value;
+        Ok(self
+            .chain(from, to)?
+            .into_iter()
+            .map(|stage| {
+                let input = value;
+                value = stage.mapping.get(input);
+                Step {
+                    stage,
+                    input,
+                    output: value,
+                }
+            })
+            .collect())
+    }
+
+    /// `value` of category `from` as category `to`
+    pub fn resolve(&self, from: &str, to: &str, value: u64) -> Result<u64> {
+        Ok(self
+            .trace(from, to, value)?
+            .last()
+            .map_or(value, |step| step.output))
+    }
+
+    /// The single mapping from category `from` to category `to`
+    pub fn compose(&self, from: &str, to: &str) -> Result<Mapping> {
+        Ok(self
+            .chain(from, to)?
+            .iter()
+            .fold(Mapping::default(), |mapping, stage| {
+                mapping.then(&stage.mapping)
+            }))
+    }
+
+    /// The seeds line read as `start length` pairs
+    pub fn seed_ranges(&self) -> Result<Vec<Range<u64>>> {
+        self.seeds
+            .chunks(2)
+            .map(|pair| match pair {
+                [start, length] => start
+                    .checked_add(*length)
+                    .map(|end| *start..end)
+                    .ok_or(anyhow!("Seed range out of range: {} {}", start, length)),
+                _ => Err(anyhow!("Seed {} has no length", pair[0])),
+            })
+            .collect()
+    }
+
+    /// The lowest location for any of the seeds
+    pub fn lowest_location(&self) -> Result<u64> {
+        let mapping = self.compose("seed", "location")?;
+        self.seeds
+            .iter()
+            .map(|&seed| mapping.get(seed))
+            .min()
+            .ok_or(anyhow!("No seeds"))
+    }
+
+    /// The lowest location for any seed in the seed ranges
+    pub fn lowest_location_for_ranges(&self) -> Result<u64> {
+        let mapping = self.compose("seed", "location")?;
+        self.seed_ranges()?
+            .into_iter()
+            .filter_map(|range| mapping.min_over(range))
+            .min()
+            .ok_or(anyhow!("No seeds"))
+    }
+
+    /// The values of category `from` that end up in `range` of category
+    /// `to`, resolved back through each stage in turn
+    pub fn sources(&self, from: &str, to: &str, range: Range<u64>) -> Result<Vec<Range<u64>>> {
+        let mut ranges = vec![range];
+        for stage in self.chain(from, to)?.iter().rev() {
+            ranges = merge(
+                ranges
+                    .into_iter()
+                    .flat_map(|range| stage.mapping.preimage(range))
+                    .collect(),
+            );
+        }
+        Ok(ranges)
+    }
+
+    /// The seeds, from the seed ranges, that end up at a location in `range`
+    pub fn seeds_for(&self, range: Range<u64>) -> Result<Vec<Range<u64>>> {
+        let sources = self.sources("seed", "location", range)?;
+        Ok(intersect(&sources, &merge(self.seed_ranges()?)))
+    }
+
+    /// The seeds, from the seed ranges, that end up at `location`
+    pub fn seeds_at(&self, location: u64) -> Result<Vec<Range<u64>>> {
+        match location.checked_add(1) {
+            Some(end) => self.seeds_for(location..end),
+            // Only seed u64::MAX ends up there, and seed ranges are half
+            // open, so they never hold it
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// The seeds, from the seed ranges, that end up at a location below
+    /// `location`
+    pub fn seeds_below(&self, location: u64) -> Result<Vec<Range<u64>>> {
+        self.seeds_for(0..location)
+    }
+
+    /// The lowest location for any seed in the seed ranges, found from the
+    /// location side: the seeds below a location only grow as the location
+    /// grows, so binary search for the first location that has any.
+    pub fn lowest_location_first(&self) -> Result<u64> {
+        // seeds_below(low) is empty, and seeds_below(high) is not
+        let mut low = 0;
+        let mut high = u64::MAX;
+        if self.seeds_below(high)?.is_empty() {
+            return Err(anyhow!("No seeds"));
+        }
+        while high - low > 1 {
+            let mid = low + (high - low) / 2;
+            match self.seeds_below(mid)?.is_empty() {
+                true => low = mid,
+                false => high = mid,
+            }
+        }
+        Ok(high - 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "seeds: 79 14 55 13
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15
+
+fertilizer-to-water map:
+49 53 8
+0 11 42
+42 0 7
+57 7 4
+
+water-to-light map:
+88 18 7
+18 25 70
+
+light-to-temperature map:
+45 77 23
+81 45 19
+68 64 13
+
+temperature-to-humidity map:
+0 69 1
+1 0 69
+
+humidity-to-location map:
+60 56 37
+56 93 4
+";
+
+    #[test]
+    fn test_mapping() {
+        let entry = |destination, source, length| MapEntry {
+            destination,
+            source,
+            length,
+        };
+        let mapping = Mapping::new([entry(50, 98, 2), entry(52, 50, 48)]).unwrap();
+        assert_eq!(mapping.get(0), 0);
+        assert_eq!(mapping.get(50), 52);
+        assert_eq!(mapping.get(97), 99);
+        assert_eq!(mapping.get(98), 50);
+        assert_eq!(mapping.get(99), 51);
+        // One past the end of a range is not mapped
+        assert_eq!(mapping.get(100), 100);
+
+        let images = mapping
+            .segments(45..100)
+            .iter()
+            .map(Piece::image)
+            .collect::<Vec<_>>();
+        assert_eq!(images, vec![45..50, 52..100, 50..52]);
+        assert_eq!(mapping.min_over(60..100), Some(50));
+
+        assert!(Mapping::new([entry(0, 10, 5), entry(100, 14, 1)]).is_err());
+        assert!(Mapping::new([entry(u64::MAX, 0, 2)]).is_err());
+    }
+
+    #[test]
+    fn test_compose() {
+        let almanac = Almanac::parse(EXAMPLE).unwrap();
+        assert_eq!(almanac.stages.len(), 7);
+        let mapping = almanac.compose("seed", "location").unwrap();
+        for seed in 0..200 {
+            let location = almanac
+                .stages
+                .iter()
+                .fold(seed, |value, stage| stage.mapping.get(value));
+            assert_eq!(mapping.get(seed), location);
+        }
+        assert_eq!(almanac.lowest_location().unwrap(), 35);
+        assert_eq!(almanac.lowest_location_for_ranges().unwrap(), 46);
+
+        assert!(almanac.compose("seed", "colour").is_err());
+        assert!(almanac.sources("seed", "colour", 0..1).is_err());
+        assert!(Almanac::parse("seeds: 1\n\nseed-to-soil:\n1 2 3").is_err());
+    }
+
+    #[test]
+    fn test_preimage() {
+        let entry = |destination, source, length| MapEntry {
+            destination,
+            source,
+            length,
+        };
+        let mapping = Mapping::new([entry(50, 98, 2), entry(52, 50, 48)]).unwrap();
+        assert_eq!(mapping.preimage(50..53), vec![50..51, 98..100]);
+        assert_eq!(mapping.preimage(10..20), vec![10..20]);
+        assert_eq!(mapping.preimage(40..60), vec![40..58, 98..100]);
+
+        let almanac = Almanac::parse(EXAMPLE).unwrap();
+        assert_eq!(almanac.seeds_at(46).unwrap(), vec![82..83]);
+        assert!(almanac.seeds_below(46).unwrap().is_empty());
+        assert_eq!(almanac.lowest_location_first().unwrap(), 46);
+        // Every seed the search finds really does end up below the bound
+        let mapping = almanac.compose("seed", "location").unwrap();
+        for range in almanac.seeds_below(60).unwrap() {
+            assert!(range.clone().all(|seed| mapping.get(seed) < 60));
+        }
+        // Seed ranges stop short of u64::MAX, so no seed ends up there, but
+        // every location below it can be found
+        assert!(almanac.seeds_at(u64::MAX).unwrap().is_empty());
+        let parse = |input: &str| Almanac::parse(input).unwrap();
+        let edge = parse(
+            "seeds: 18446744073709551613 2\n\nseed-to-location map:\n0 18446744073709551614 1",
+        );
+        assert_eq!(edge.seeds_at(0).unwrap(), vec![u64::MAX - 1..u64::MAX]);
+        assert_eq!(edge.lowest_location_first().unwrap(), 0);
+        let edge = parse("seeds: 18446744073709551614 1\n\nseed-to-location map:\n1 0 1");
+        assert_eq!(edge.lowest_location_first().unwrap(), u64::MAX - 1);
+        let mapping = edge.compose("seed", "location").unwrap();
+        assert_eq!(mapping.get(u64::MAX), u64::MAX);
+        assert!(mapping
+            .preimage(u64::MAX - 1..u64::MAX)
+            .contains(&(u64::MAX - 1..u64::MAX)));
+
+        let sources = almanac.sources("soil", "humidity", 0..10).unwrap();
+        let mapping = almanac.compose("soil", "humidity").unwrap();
+        for soil in 0..200 {
+            let found = sources.iter().any(|range| range.contains(&soil));
+            assert_eq!(found, mapping.get(soil) < 10);
+        }
+    }
+
+    #[test]
+    fn test_graph() {
+        let almanac = Almanac::parse(EXAMPLE).unwrap();
+        let steps = almanac.trace("seed", "location", 79).unwrap();
+        let values = steps.iter().map(|step| step.output).collect::<Vec<_>>();
+        assert_eq!(values, vec![81, 81, 81, 74, 78, 78, 82]);
+        let changed = steps
+            .iter()
+            .filter(|step| step.changed())
+            .map(|step| step.stage.source.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(changed, vec!["seed", "water", "light", "humidity"]);
+        assert_eq!(steps[0].to_string(), "seed 79 -> soil 81 (changed)");
+        assert_eq!(almanac.resolve("soil", "humidity", 81).unwrap(), 78);
+        assert_eq!(almanac.resolve("soil", "soil", 81).unwrap(), 81);
+
+        let err = |input: &str| Almanac::parse(input).unwrap_err().to_string();
+        // Backwards along the chain
+        assert_eq!(
+            almanac.resolve("soil", "seed", 1).unwrap_err().to_string(),
+            "No maps lead from soil to seed"
+        );
+        assert_eq!(
+            almanac
+                .resolve("seed", "colour", 1)
+                .unwrap_err()
+                .to_string(),
+            "No category colour"
+        );
+
+        // A shortcut from a to c, and a branch to d
+        let input = "seeds: 1
+
+a-to-b map:
+10 0 5
+
+b-to-c map:
+20 10 5
+
+a-to-c map:
+30 0 5
+
+b-to-d map:
+40 10 5";
+        let almanac = Almanac::parse(input).unwrap();
+        assert_eq!(almanac.resolve("a", "c", 1).unwrap(), 31);
+        assert_eq!(almanac.resolve("a", "d", 1).unwrap(), 41);
+        assert_eq!(almanac.resolve("b", "c", 11).unwrap(), 21);
+        assert!(almanac.resolve("c", "d", 1).is_err());
+
+        // Two paths of the same length that disagree: the one through the
+        // stage that comes first wins
+        let via_b = "a-to-b map:\n10 0 5\n\nb-to-d map:\n20 10 5";
+        let via_c = "a-to-c map:\n30 0 5\n\nc-to-d map:\n40 30 5";
+        let tied = |first: &str, second: &str| {
+            let input = format!("seeds: 1\n\n{}\n\n{}", first, second);
+            Almanac::parse(&input)
+                .unwrap()
+                .resolve("a", "d", 1)
+                .unwrap()
+        };
+        assert_eq!(tied(via_b, via_c), 21);
+        assert_eq!(tied(via_c, via_b), 41);
+
+        assert_eq!(
+            err(&format!("{}\n\nd-to-b map:\n0 0 1", input)),
+            "Maps loop: b -> d -> b"
+        );
+        assert_eq!(
+            err(&format!("{}\n\na-to-b map:\n0 0 1", input)),
+            "More than one map from a to b"
+        );
+    }
+}
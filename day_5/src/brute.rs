@@ -1,314 +1,223 @@
-use anyhow::Result;
-#[derive(Debug)]
-struct MapEntry {
-    destination: usize,
-    source: usize,
-    range: usize,
+//! Brute
+//! A brute force solver to check [Almanac] against.  Every seed is walked
+//! through every stage, looking for the map entry that covers it, so it
+//! only suits the small almanacs [Case::generate] makes.  Run the two
+//! against each other with [utils::find_disagreement].
+//!
+//! This is a new reference solver, written for the differential tests.  It
+//! is not the brute force solver this day used to have: that one also
+//! matched the value one past the end of an entry.  The `OffByOne` test
+//! double keeps that bug, to show the tests catch it.
+use anyhow::{anyhow, Result};
+use utils::{Rng, Shrink, Solution};
+
+use super::almanac::Almanac;
+
+/// A generated almanac: the seeds, and the `(destination, source, length)`
+/// entries of each stage.  The first stage maps from `seed`, and the last
+/// maps to `location`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Case {
+    pub seeds: Vec<u64>,
+    pub stages: Vec<Vec<(u64, u64, u64)>>,
 }
 
-impl std::fmt::Display for MapEntry {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{} {} {}", &self.destination, &self.source, &self.range)
-    }
-}
-impl MapEntry {
-    fn map_value(&self, value: usize) -> usize {
-        if value >= self.source && value <= self.source + self.range {
-            return self.destination + (value - self.source);
+impl Case {
+    pub fn generate(rng: &mut Rng) -> Self {
+        let seeds = (0..1 + rng.below(3))
+            .flat_map(|_| [rng.below(100), 1 + rng.below(20)])
+            .collect();
+        let stages = (0..1 + rng.below(4))
+            .map(|_| {
+                let mut start = 0;
+                (0..rng.below(5))
+                    .map(|_| {
+                        let source = start + rng.below(10);
+                        let length = 1 + rng.below(10);
+                        start = source + length;
+                        (rng.below(100), source, length)
+                    })
+                    .collect()
+            })
+            .collect();
+        Self { seeds, stages }
+    }
+
+    /// Whether the almanac is one a solver should answer: seed ranges, and
+    /// at least one stage, without overlapping entries
+    fn is_valid(&self) -> bool {
+        let no_overlaps = |entries: &Vec<(u64, u64, u64)>| {
+            let mut sources = entries
+                .iter()
+                .map(|&(_, source, length)| (source, source + length))
+                .collect::<Vec<_>>();
+            sources.sort();
+            sources.windows(2).all(|pair| pair[0].1 <= pair[1].0)
+        };
+        !self.seeds.is_empty()
+            && self.seeds.len().is_multiple_of(2)
+            && !self.stages.is_empty()
+            && self.stages.iter().all(no_overlaps)
+    }
+
+    fn category(&self, idx: usize) -> String {
+        match idx {
+            0 => "seed".to_string(),
+            _ if idx == self.stages.len() => "location".to_string(),
+            _ => format!("category{}", idx),
         }
-        value
     }
 }
 
-impl TryFrom<&str> for MapEntry {
-    type Error = String;
-    fn try_from(value: &str) -> Result<Self, Self::Error> {
-        let mut parts = value.split(' ');
-        let destination = parts
-            .next()
-            .ok_or("No desitionation".to_string())?
-            .parse::<usize>()
-            .map_err(|e| e.to_string())?;
-        let source = parts
-            .next()
-            .ok_or("No source".to_string())?
-            .parse::<usize>()
-            .map_err(|e| e.to_string())?;
-        let range = parts
-            .next()
-            .ok_or("No range".to_string())?
-            .parse::<usize>()
-            .map_err(|e| e.to_string())?;
-
-        Ok(MapEntry {
-            destination,
-            source,
-            range,
-        })
+/// Shrinks to valid almanacs only, so a disagreement is never just one
+/// solver rejecting the input
+impl Shrink for Case {
+    fn shrink(&self) -> Vec<Self> {
+        (self.seeds.clone(), self.stages.clone())
+            .shrink()
+            .into_iter()
+            .map(|(seeds, stages)| Self { seeds, stages })
+            .filter(Case::is_valid)
+            .collect()
     }
 }
 
-#[derive(Debug, Default)]
-struct AlmanacEntry {
-    seed: usize,
-    soil: usize,
-    fertilizer: usize,
-    water: usize,
-    light: usize,
-    temperature: usize,
-    humidity: usize,
-    location: usize,
-}
-
-impl std::fmt::Display for AlmanacEntry {
+/// The almanac as puzzle input
+impl std::fmt::Display for Case {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "Seed {}, soil {}, fertilizer {}, water {}, light {}, temerature {}, humidity {}, location {}",
-            self.seed,
-            self.soil,
-            self.fertilizer,
-            self.water,
-            self.light,
-            self.temperature,
-            self.humidity,
-            self.location
-        )
-    }
-}
-
-fn map_maps(value: usize, entries: &Vec<MapEntry>) -> usize {
-    for entry in entries {
-        let tmp_value = entry.map_value(value);
-        if tmp_value != value {
-            return tmp_value;
+        let seeds = self.seeds.iter().map(u64::to_string).collect::<Vec<_>>();
+        writeln!(f, "seeds: {}", seeds.join(" "))?;
+        for (idx, entries) in self.stages.iter().enumerate() {
+            writeln!(f)?;
+            writeln!(
+                f,
+                "{}-to-{} map:",
+                self.category(idx),
+                self.category(idx + 1)
+            )?;
+            for (destination, source, length) in entries {
+                writeln!(f, "{} {} {}", destination, source, length)?;
+            }
         }
+        Ok(())
     }
-    value
-}
-
-#[derive(Debug, Default)]
-struct Almanac {
-    seeds: Vec<usize>,
-    seed_to_soil: Vec<MapEntry>,
-    soil_to_fertilizer: Vec<MapEntry>,
-    fertilizer_to_water: Vec<MapEntry>,
-    water_to_light: Vec<MapEntry>,
-    light_to_temperature: Vec<MapEntry>,
-    temperature_to_humidity: Vec<MapEntry>,
-    humidity_to_location: Vec<MapEntry>,
 }
 
-impl std::fmt::Display for Almanac {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut result = String::new();
-        result.push_str(&format!("seeds: {}\n", nums_to_str(&self.seeds)));
-
-        result.push_str("\nseed-to-soil\n");
-        for entry in &self.seed_to_soil {
-            result.push_str(&format!("{}\n", entry));
-        }
-
-        result.push_str("\nsoil-to-fertilizer\n");
-        for entry in &self.soil_to_fertilizer {
-            result.push_str(&format!("{}\n", entry));
-        }
-
-        result.push_str("\nfertilizer-to-water\n");
-        for entry in &self.fertilizer_to_water {
-            result.push_str(&format!("{}\n", entry));
-        }
-
-        result.push_str("\nwater-to-light\n");
-        for entry in &self.water_to_light {
-            result.push_str(&format!("{}\n", entry));
-        }
-
-        result.push_str("\nlight-to-temperature\n");
-        for entry in &self.light_to_temperature {
-            result.push_str(&format!("{}\n", entry));
-        }
-
-        result.push_str("\ntemperature-to-humidity\n");
-        for entry in &self.temperature_to_humidity {
-            result.push_str(&format!("{}\n", entry));
-        }
-
-        result.push_str("\nhumidity-to-location\n");
-        for entry in &self.humidity_to_location {
-            result.push_str(&format!("{}\n", entry));
-        }
-
-        write!(f, "{}", result)
-    }
-}
-impl Almanac {
-    fn entry(&self, seed: usize) -> AlmanacEntry {
-        let soil = map_maps(seed, &self.seed_to_soil);
-        let fertilizer = map_maps(soil, &self.soil_to_fertilizer);
-        let water = map_maps(fertilizer, &self.fertilizer_to_water);
-        let light = map_maps(water, &self.water_to_light);
-        let temperature = map_maps(light, &self.light_to_temperature);
-        let humidity = map_maps(temperature, &self.temperature_to_humidity);
-        let location = map_maps(humidity, &self.humidity_to_location);
-
-        AlmanacEntry {
-            seed,
-            soil,
-            fertilizer,
-            water,
-            light,
-            temperature,
-            humidity,
-            location,
-        }
-    }
-
-    fn entries(&self) -> Vec<AlmanacEntry> {
-        let mut entries: Vec<AlmanacEntry> = Vec::new();
-
-        for seed in &self.seeds {
-            entries.push(self.entry(*seed))
-        }
-        entries
+/// Part one and part two by walking every seed
+pub struct Brute;
+
+impl Brute {
+    fn location(case: &Case, seed: u64) -> u64 {
+        case.stages.iter().fold(seed, |value, entries| {
+            entries
+                .iter()
+                .find(|&&(_, source, length)| value >= source && value < source + length)
+                .map_or(value, |&(destination, source, _)| {
+                    destination + (value - source)
+                })
+        })
     }
 }
 
-pub fn part_one(input: &str) -> Result<()> {
-    let almanac = parse_almanac(input)?;
-
-    let entries = almanac.entries();
-    let mut min_loc = entries[0].location;
-    for entry in &entries {
-        min_loc = std::cmp::min(min_loc, entry.location);
+impl Solution for Brute {
+    type Input = Case;
+    type Output = (u64, u64);
+
+    fn solve(&self, case: &Case) -> Result<(u64, u64)> {
+        let one = case
+            .seeds
+            .iter()
+            .map(|&seed| Self::location(case, seed))
+            .min();
+        let two = case
+            .seeds
+            .chunks(2)
+            .flat_map(|pair| pair[0]..pair[0] + pair[1])
+            .map(|seed| Self::location(case, seed))
+            .min();
+        one.zip(two).ok_or(anyhow!("No seeds"))
     }
-    println!("Part One: {}", min_loc);
-    Ok(())
 }
-pub fn part_two(_input: &str) -> Result<()> {
-    println!("Part Two:");
-    Ok(())
-}
-
-fn nums_to_str(v: &[usize]) -> String {
-    let nums = v.iter().map(|v| v.to_string()).collect::<Vec<String>>();
-    nums.join(", ")
-}
-
-fn parse_almanac(input: &str) -> Result<Almanac> {
-    let mut almanac = Almanac::default();
-    let lines = input.split('\n').collect::<Vec<&str>>();
 
-    let mut cursor = 0;
-
-    let seeds_line = lines[cursor];
-    let seeds_parts = seeds_line.split(':').collect::<Vec<&str>>();
-    let seeds = seeds_parts[1].trim().split(' ').collect::<Vec<&str>>();
-    almanac.seeds = seeds
-        .iter()
-        .map(|s| s.parse::<usize>().unwrap())
-        .collect::<Vec<usize>>();
-    // Skip the empty line and title line
-    cursor += 2;
-
-    assert_eq!(lines[cursor], "seed-to-soil map:");
-    cursor += 1;
-    // Process the seed-to-soil map
-    while !lines[cursor].is_empty() {
-        let entry = MapEntry::try_from(lines[cursor])?;
-        almanac.seed_to_soil.push(entry);
-        cursor += 1
-    }
-    cursor += 1;
-    assert_eq!(lines[cursor], "soil-to-fertilizer map:");
-    cursor += 1;
-
-    // Process soil-to-fertilizer
-    while !lines[cursor].is_empty() {
-        let entry = MapEntry::try_from(lines[cursor])?;
-        almanac.soil_to_fertilizer.push(entry);
-        cursor += 1
-    }
-    cursor += 1;
-    assert_eq!(lines[cursor], "fertilizer-to-water map:");
-    cursor += 1;
-
-    // Process fertilizer-to-water
-    while !lines[cursor].is_empty() {
-        let entry = MapEntry::try_from(lines[cursor])?;
-        almanac.fertilizer_to_water.push(entry);
-
-        cursor += 1
-    }
-    cursor += 1;
-    assert_eq!(lines[cursor], "water-to-light map:");
-    cursor += 1;
+/// Part one and part two from the composed [Almanac]
+pub struct Composed;
 
-    // Process water-to-light
-    while !lines[cursor].is_empty() {
-        let entry = MapEntry::try_from(lines[cursor])?;
-        almanac.water_to_light.push(entry);
+impl Solution for Composed {
+    type Input = Case;
+    type Output = (u64, u64);
 
-        cursor += 1
+    fn solve(&self, case: &Case) -> Result<(u64, u64)> {
+        let almanac = Almanac::parse(&case.to_string())?;
+        Ok((
+            almanac.lowest_location()?,
+            almanac.lowest_location_for_ranges()?,
+        ))
     }
-    cursor += 1;
-    assert_eq!(lines[cursor], "light-to-temperature map:");
-    cursor += 1;
-
-    // Process light-to-temperature
-    while !lines[cursor].is_empty() {
-        let entry = MapEntry::try_from(lines[cursor])?;
-        almanac.light_to_temperature.push(entry);
-
-        cursor += 1
-    }
-    cursor += 1;
-    assert_eq!(lines[cursor], "temperature-to-humidity map:");
-    cursor += 1;
-
-    // Process temerature-to-humidity
-    while !lines[cursor].is_empty() {
-        let entry = MapEntry::try_from(lines[cursor])?;
-        almanac.temperature_to_humidity.push(entry);
-        cursor += 1
-    }
-    cursor += 1;
-    assert_eq!(lines[cursor], "humidity-to-location map:");
-    cursor += 1;
-
-    // Process humidity-to-location
-    while cursor < lines.len() {
-        let entry = MapEntry::try_from(lines[cursor])?;
-        almanac.humidity_to_location.push(entry);
-
-        cursor += 1
-    }
-
-    Ok(almanac)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use utils::find_disagreement;
+
+    /// The original brute force solver's entry lookup, which also matched
+    /// the value one past the end of an entry
+    struct OffByOne;
+
+    impl Solution for OffByOne {
+        type Input = Case;
+        type Output = (u64, u64);
+
+        fn solve(&self, case: &Case) -> Result<(u64, u64)> {
+            let location = |seed: u64| {
+                case.stages.iter().fold(seed, |value, entries| {
+                    entries
+                        .iter()
+                        .find(|&&(_, source, length)| value >= source && value <= source + length)
+                        .map_or(value, |&(destination, source, _)| {
+                            destination + (value - source)
+                        })
+                })
+            };
+            let one = case.seeds.iter().map(|&seed| location(seed)).min();
+            let two = case
+                .seeds
+                .chunks(2)
+                .flat_map(|pair| pair[0]..pair[0] + pair[1])
+                .map(location)
+                .min();
+            one.zip(two).ok_or(anyhow!("No seeds"))
+        }
+    }
+
+    #[test]
+    fn test_agree() {
+        assert!(find_disagreement(&Brute, &Composed, 500, 5, Case::generate).is_none());
+    }
 
     #[test]
-    fn test_maps() {
-        let maps = vec![
-            MapEntry {
-                destination: 50,
-                source: 98,
-                range: 2,
-            },
-            MapEntry {
-                destination: 52,
-                source: 50,
-                range: 48,
-            },
-        ];
+    fn test_end_of_entry() {
+        // A seed exactly at `source + length` is past the entry, so it
+        // keeps its value.  Part one also reads the length, 1, as a seed.
+        let case = Case {
+            seeds: vec![5, 1],
+            stages: vec![vec![(100, 0, 5)]],
+        };
+        assert_eq!(Brute.solve(&case).unwrap(), (5, 5));
+        assert_eq!(Composed.solve(&case).unwrap(), (5, 5));
+        assert_eq!(OffByOne.solve(&case).unwrap(), (101, 105));
+    }
 
-        assert_eq!(0, map_maps(0, &maps));
-        assert_eq!(52, map_maps(50, &maps));
-        assert_eq!(53, map_maps(51, &maps));
-        assert_eq!(50, map_maps(98, &maps));
+    #[test]
+    fn test_off_by_one() {
+        let found = find_disagreement(&OffByOne, &Composed, 500, 5, Case::generate)
+            .expect("Off by one is caught");
+        // Shrunk to a single entry, and a seed just past its end
+        let case = &found.input;
+        assert_eq!(case.stages.len(), 1);
+        assert_eq!(case.stages[0].len(), 1);
+        let (_, source, length) = case.stages[0][0];
+        assert!(case.seeds.contains(&(source + length)));
+        assert_ne!(found.left.unwrap(), found.right.unwrap());
     }
 }
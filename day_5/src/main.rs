@@ -1,14 +1,119 @@
-use anyhow::Result;
-use ranges::*;
-//mod brute::*;
-mod ranges;
+use almanac::Almanac;
+use anyhow::{anyhow, Result};
+use brute::{Brute, Case, Composed};
 
-/// While I did succeed at developing a brute force method, I failed at
-/// developing an elegant, range based solution.  I stole it.
+mod almanac;
+mod brute;
+
+/// Check the composed almanac against brute force on `cases` generated
+/// almanacs.  The seed is printed, so a failure can be reproduced with
+/// `--seed`.  A disagreement is an error.
+fn differential(cases: u64, seed: u64) -> Result<()> {
+    let timer = std::time::Instant::now();
+    match utils::find_disagreement(&Brute, &Composed, cases as usize, seed, Case::generate) {
+        None => {
+            println!(
+                "No disagreement in {} cases, seed {} -- {:?}",
+                cases,
+                seed,
+                timer.elapsed()
+            );
+            Ok(())
+        }
+        Some(found) => {
+            println!("{}", found.input);
+            println!("Brute: {:?}", found.left);
+            println!("Composed: {:?}", found.right);
+            Err(anyhow!(
+                "Brute force and the almanac disagree, seed {}",
+                seed
+            ))
+        }
+    }
+}
+
+/// The argument after `flag`
+fn value(args: &mut impl Iterator<Item = String>, flag: &str) -> Result<String> {
+    args.next().ok_or_else(|| anyhow!("{} needs a value", flag))
+}
+
+fn part_one(almanac: &Almanac) -> Result<()> {
+    let timer = std::time::Instant::now();
+    let result = almanac.lowest_location()?;
+    println!("Part One: {} -- {:?}", result, timer.elapsed());
+    Ok(())
+}
+
+fn part_two(almanac: &Almanac) -> Result<()> {
+    let timer = std::time::Instant::now();
+    let result = almanac.lowest_location_for_ranges()?;
+    println!("Part Two: {} -- {:?}", result, timer.elapsed());
+    Ok(())
+}
+
+/// Run with `--location-first` to solve part two from the location side,
+/// `--seeds-at <location>` to list the seeds that end up at a location, or
+/// `--seeds-below <location>` to list the seeds that end up below one.
+/// `--trace <seed>` shows the seed through each stage, and
+/// `--resolve <from> <to> <value>` maps a value between any two categories.
+/// `--differential <cases>` checks the solver against brute force, with
+/// `--seed <n>` to repeat a run.
 fn main() -> Result<()> {
     let input = include_str!("../puzzle_input.txt");
+    let almanac = Almanac::parse(input)?;
+
+    part_one(&almanac)?;
+    part_two(&almanac)?;
 
-    part_one(input)?;
-    part_two(input)?;
+    let mut cases = None;
+    let mut seed = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--location-first" => {
+                let timer = std::time::Instant::now();
+                let result = almanac.lowest_location_first()?;
+                println!("Location first: {} -- {:?}", result, timer.elapsed());
+            }
+            "--seeds-at" => {
+                let location = value(&mut args, &arg)?.parse()?;
+                println!("{:?}", almanac.seeds_at(location)?)
+            }
+            "--seeds-below" => {
+                let location = value(&mut args, &arg)?.parse()?;
+                println!("{:?}", almanac.seeds_below(location)?)
+            }
+            "--trace" => {
+                let seed = value(&mut args, &arg)?.parse()?;
+                for step in almanac.trace("seed", "location", seed)? {
+                    println!("{}", step);
+                }
+            }
+            "--differential" => cases = Some(value(&mut args, &arg)?.parse()?),
+            "--seed" => seed = Some(value(&mut args, &arg)?.parse()?),
+            "--resolve" => {
+                let usage = || anyhow!("--resolve needs <from> <to> <value>");
+                let from = args.next().ok_or_else(usage)?;
+                let to = args.next().ok_or_else(usage)?;
+                let value = args.next().ok_or_else(usage)?;
+                let result = almanac.resolve(&from, &to, value.parse()?)?;
+                println!("{} {} is {} {}", from, value, to, result);
+            }
+            _ => return Err(anyhow!("Unknown argument: {}", arg)),
+        }
+    }
+    match (cases, seed) {
+        (Some(cases), seed) => {
+            let seed = match seed {
+                Some(seed) => seed,
+                None => std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)?
+                    .as_nanos() as u64,
+            };
+            differential(cases, seed)?
+        }
+        (None, Some(_)) => return Err(anyhow!("--seed is only used with --differential")),
+        (None, None) => (),
+    }
     Ok(())
 }
@@ -1,4 +1,6 @@
 use anyhow::{anyhow, Result};
+use utils::numbers;
+
 #[derive(Debug, PartialEq)]
 struct Race {
     time: usize,
@@ -53,16 +55,10 @@ fn parse_input(puzzle_input: &str) -> Result<Vec<Race>> {
         if let Some((category, s)) = line.split_once(':') {
             match category {
                 "Time" => {
-                    times = s
-                        .split_whitespace()
-                        .map(|n| n.parse::<usize>().unwrap())
-                        .collect::<Vec<usize>>();
+                    times = numbers::<usize>(s).collect::<Result<Vec<usize>>>()?;
                 }
                 "Distance" => {
-                    distances = s
-                        .split_whitespace()
-                        .map(|n| n.parse::<usize>().unwrap())
-                        .collect::<Vec<usize>>();
+                    distances = numbers::<usize>(s).collect::<Result<Vec<usize>>>()?;
                 }
                 _ => return Err(anyhow!("Unexpected category {}", category)),
             };
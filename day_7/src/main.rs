@@ -1,6 +1,7 @@
 use anyhow::{anyhow, Result};
 /// Thanks to https://www.reddit.com/user/KyleGBC/ for hings on ordering!
 use std::collections::HashMap;
+use utils::AocParse;
 
 fn map_card(card: char, wilds: bool) -> u32 {
     match card {
@@ -98,21 +99,33 @@ impl Hand {
     }
 
     fn from_str(value: &str, use_wilds: bool) -> Result<Self> {
-        let parts = value.split(' ').collect::<Vec<&str>>();
+        let deal: Deal = value.parse()?;
 
-        if parts[0].len() != 5 {
+        if deal.cards.len() != 5 {
             return Err(anyhow!("wrong number of cards"));
         }
-        let cards = parts[0]
+        let cards = deal
+            .cards
             .chars()
             .map(|c| map_card(c, use_wilds))
             .collect::<Vec<u32>>();
-        let bid = parts[1].parse::<u32>().unwrap();
         let kind = HandKind::eval(&cards, use_wilds)?;
-        Ok(Hand { cards, bid, kind })
+        Ok(Hand {
+            cards,
+            bid: deal.bid,
+            kind,
+        })
     }
 }
 
+/// A line of input, before the cards are valued
+#[derive(Debug, AocParse)]
+#[aoc("{cards} {bid}")]
+struct Deal {
+    cards: String,
+    bid: u32,
+}
+
 impl Ord for Hand {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
         if self.kind != other.kind {
@@ -1,6 +1,7 @@
 /// Thank you to https://github.com/timvisee/advent-of-code-2023/blob/master/day09b/src/main.rs
 /// for the guidance on using Pascal's Triangle
 use anyhow::Result;
+use utils::numbers;
 
 fn pascal(size: usize) -> Vec<Vec<isize>> {
     let mut triangle: Vec<Vec<isize>> = vec![vec![1]];
@@ -82,10 +83,7 @@ fn main() -> Result<()> {
 fn parse_input(input: &str) -> Result<Vec<Vec<isize>>> {
     let mut result: Vec<Vec<isize>> = Vec::new();
     for line in input.lines() {
-        let values = line
-            .split(' ')
-            .map(|s| s.parse::<isize>().map_err(|e| e.into()))
-            .collect::<Result<Vec<isize>>>()?;
+        let values = numbers::<isize>(line).collect::<Result<Vec<isize>>>()?;
         result.push(values);
     }
 
@@ -0,0 +1,268 @@
+//! ArrayGrid
+//! A fixed size grid, stored inline as `[[T; W]; H]`.  Use it when the
+//! puzzle input size is known up front.  When it might not be, parse into
+//! an [AnyGrid], which uses an [ArrayGrid] if the input is exactly `W` x `H`
+//! and falls back to a heap allocated [Grid] otherwise.
+//!
+//! Both implement [GridLike], so code written against [GridLike] works on
+//! any input size, including walking it with a [crate::Walker].  For the
+//! rest of the [Grid] API, such as views, use [AnyGrid::into_grid].
+//!
+//! ```rust
+//! # use utils::{AnyGrid, ArrayGrid, GridLike};
+//! let input = "12\n34";
+//! let convert = |c: char| Ok(c.to_digit(10).unwrap());
+//! let grid = ArrayGrid::<u32, 2, 2>::parse_undelim_str(input, convert).unwrap();
+//! assert_eq!(grid.get_at(1, 1).unwrap(), &4);
+//!
+//! let grid = AnyGrid::<i32, u32, 3, 3>::parse_undelim_str(input, convert).unwrap();
+//! assert!(matches!(grid, AnyGrid::Dynamic(_)));
+//! assert_eq!(grid.width(), 2);
+//! ```
+use super::{grid::parse_rows, Grid, GridLike};
+use anyhow::{anyhow, Result};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArrayGrid<T, const W: usize, const H: usize> {
+    pub cells: [[T; W]; H],
+}
+
+impl<T: Copy, const W: usize, const H: usize> ArrayGrid<T, W, H> {
+    /// Create a grid with every cell set to `fill`
+    pub fn new(fill: T) -> Self {
+        Self {
+            cells: [[fill; W]; H],
+        }
+    }
+}
+
+impl<T, const W: usize, const H: usize> ArrayGrid<T, W, H> {
+    #[inline]
+    pub fn width(&self) -> usize {
+        W
+    }
+
+    #[inline]
+    pub fn height(&self) -> usize {
+        H
+    }
+
+    pub fn get_at(&self, x: usize, y: usize) -> Result<&T> {
+        if x >= W || y >= H {
+            return Err(anyhow!("Out of bounds: {},{}", x, y));
+        }
+        Ok(&self.cells[y][x])
+    }
+
+    pub fn get_at_mut(&mut self, x: usize, y: usize) -> Result<&mut T> {
+        if x >= W || y >= H {
+            return Err(anyhow!("Out of bounds: {},{}", x, y));
+        }
+        Ok(&mut self.cells[y][x])
+    }
+
+    /// Parse PATTERN delimited lines.  The input must be exactly `W` x `H`.
+    /// See [Grid::parse_str].
+    pub fn parse_str<F>(input: &str, pattern: &str, convert: F) -> Result<Self>
+    where
+        F: Fn(&str) -> Result<T>,
+    {
+        let rows = parse_rows(input, |line| line.split(pattern).map(&convert).collect())?;
+        Self::from_rows(rows)
+    }
+
+    /// Parse lines of chars.  The input must be exactly `W` x `H`.
+    /// See [Grid::parse_undelim_str].
+    pub fn parse_undelim_str<F>(input: &str, convert: F) -> Result<Self>
+    where
+        F: Fn(char) -> Result<T>,
+    {
+        let rows = parse_rows(input, |line| line.chars().map(&convert).collect())?;
+        Self::from_rows(rows)
+    }
+
+    fn from_rows(rows: Vec<Vec<T>>) -> Result<Self> {
+        if rows.len() != H {
+            return Err(anyhow!("Expected {} lines, found {}", H, rows.len()));
+        }
+        let mut cells: Vec<[T; W]> = Vec::with_capacity(H);
+        for (idx, row) in rows.into_iter().enumerate() {
+            let len = row.len();
+            let row: [T; W] = row
+                .try_into()
+                .map_err(|_| anyhow!("Line {}: expected {} cells, found {}", idx + 1, W, len))?;
+            cells.push(row);
+        }
+        let cells: [[T; W]; H] = cells.try_into().map_err(|_| anyhow!("Wrong height"))?;
+        Ok(Self { cells })
+    }
+
+    fn fits(rows: &[Vec<T>]) -> bool {
+        rows.len() == H && rows.iter().all(|row| row.len() == W)
+    }
+}
+
+impl<T, const W: usize, const H: usize> GridLike<T> for ArrayGrid<T, W, H> {
+    fn width(&self) -> usize {
+        W
+    }
+
+    fn height(&self) -> usize {
+        H
+    }
+
+    fn get_at(&self, x: usize, y: usize) -> Result<&T> {
+        ArrayGrid::get_at(self, x, y)
+    }
+
+    fn get_at_mut(&mut self, x: usize, y: usize) -> Result<&mut T> {
+        ArrayGrid::get_at_mut(self, x, y)
+    }
+}
+
+/// Either a fixed `W` x `H` [ArrayGrid], or a [Grid] for any other size.
+#[derive(Debug)]
+pub enum AnyGrid<P, T, const W: usize, const H: usize> {
+    Array(ArrayGrid<T, W, H>),
+    Dynamic(Grid<P, T>),
+}
+
+impl<
+        P: std::fmt::Display + num::Integer + num::PrimInt + num::FromPrimitive + Sync + Send,
+        T: std::fmt::Debug + Sized + Send + Sync,
+        const W: usize,
+        const H: usize,
+    > AnyGrid<P, T, W, H>
+{
+    /// Parse PATTERN delimited lines into an [ArrayGrid] if the input is
+    /// `W` x `H`, otherwise into a [Grid].
+    pub fn parse_str<F>(input: &str, pattern: &str, convert: F) -> Result<Self>
+    where
+        F: Fn(&str) -> Result<T>,
+    {
+        let rows = parse_rows(input, |line| line.split(pattern).map(&convert).collect())?;
+        Self::from_rows(rows)
+    }
+
+    /// Parse lines of chars into an [ArrayGrid] if the input is `W` x `H`,
+    /// otherwise into a [Grid].
+    pub fn parse_undelim_str<F>(input: &str, convert: F) -> Result<Self>
+    where
+        F: Fn(char) -> Result<T>,
+    {
+        let rows = parse_rows(input, |line| line.chars().map(&convert).collect())?;
+        Self::from_rows(rows)
+    }
+
+    /// Move the cells into a [Grid]
+    pub fn into_grid(self) -> Grid<P, T> {
+        match self {
+            Self::Array(grid) => {
+                let cells = grid.cells.into_iter().flatten().collect();
+                Grid::from_cells(cells, W, H)
+            }
+            Self::Dynamic(grid) => grid,
+        }
+    }
+
+    fn from_rows(rows: Vec<Vec<T>>) -> Result<Self> {
+        match ArrayGrid::<T, W, H>::fits(&rows) {
+            true => Ok(Self::Array(ArrayGrid::from_rows(rows)?)),
+            false => Ok(Self::Dynamic(Grid::from_rows(rows)?)),
+        }
+    }
+}
+
+impl<
+        P: std::fmt::Display + num::Integer + num::PrimInt + num::FromPrimitive + Sync + Send,
+        T: std::fmt::Debug + Sized + Send + Sync,
+        const W: usize,
+        const H: usize,
+    > GridLike<T> for AnyGrid<P, T, W, H>
+{
+    fn width(&self) -> usize {
+        match self {
+            Self::Array(grid) => grid.width(),
+            Self::Dynamic(grid) => grid.width(),
+        }
+    }
+
+    fn height(&self) -> usize {
+        match self {
+            Self::Array(grid) => grid.height(),
+            Self::Dynamic(grid) => grid.height(),
+        }
+    }
+
+    fn get_at(&self, x: usize, y: usize) -> Result<&T> {
+        match self {
+            Self::Array(grid) => grid.get_at(x, y),
+            Self::Dynamic(grid) => grid.get_at(x, y),
+        }
+    }
+
+    fn get_at_mut(&mut self, x: usize, y: usize) -> Result<&mut T> {
+        match self {
+            Self::Array(grid) => grid.get_at_mut(x, y),
+            Self::Dynamic(grid) => grid.get_at_mut(x, y),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Direction, Point};
+
+    /// Written once, for any kind of grid
+    fn count<G: GridLike<char>>(grid: &G, c: char) -> usize {
+        (0..grid.height())
+            .flat_map(|y| (0..grid.width()).map(move |x| (x, y)))
+            .filter(|(x, y)| grid.get_at(*x, *y).unwrap() == &c)
+            .count()
+    }
+
+    #[test]
+    fn test_array_grid() {
+        let mut grid = ArrayGrid::<char, 3, 2>::parse_undelim_str("#.#\n..#\n", Ok).unwrap();
+        assert_eq!(grid.get(&Point::<i32>::new(2, 1)).unwrap(), &'#');
+        assert!(grid.get_at(3, 0).is_err());
+        *grid.get_at_mut(1, 1).unwrap() = '#';
+        assert_eq!(count(&grid, '#'), 4);
+        assert_eq!(grid.bounds::<i32>(), Point::new(2, 1));
+
+        let err = ArrayGrid::<char, 3, 2>::parse_undelim_str("#.#\n..", Ok).unwrap_err();
+        assert!(err.to_string().starts_with("Line 2:"));
+        assert!(ArrayGrid::<char, 3, 2>::parse_undelim_str("#.#", Ok).is_err());
+    }
+
+    #[test]
+    fn test_any_grid() {
+        let grid = AnyGrid::<i32, char, 3, 2>::parse_undelim_str("#.#\n..#", Ok).unwrap();
+        assert!(matches!(grid, AnyGrid::Array(_)));
+        assert_eq!(count(&grid, '#'), 3);
+
+        let grid = AnyGrid::<i32, char, 3, 2>::parse_undelim_str("#.\n.#\n##", Ok).unwrap();
+        assert!(matches!(grid, AnyGrid::Dynamic(_)));
+        assert_eq!(grid.height(), 3);
+        assert_eq!(count(&grid, '#'), 4);
+
+        assert!(AnyGrid::<i32, char, 3, 2>::parse_undelim_str("#.\n.", Ok).is_err());
+    }
+
+    #[test]
+    fn test_shared_api() {
+        let grid = AnyGrid::<i32, char, 3, 2>::parse_undelim_str("abc\ndef", Ok).unwrap();
+        assert_eq!(grid.bounds::<i32>(), Point::new(2, 1));
+        let mut walker = grid.walker(Point::<i32>::new(0, 1), Direction::East);
+        assert_eq!(walker.step(), Some(Point::new(1, 1)));
+        walker.turn_left();
+        assert_eq!(walker.step(), Some(Point::new(1, 0)));
+        assert_eq!(walker.step(), None);
+        assert_eq!(walker.get().unwrap(), &'b');
+
+        let grid = grid.into_grid();
+        assert_eq!(grid.cells, "abcdef".chars().collect::<Vec<_>>());
+        assert_eq!(grid.get(&Point::new(2, 1)).unwrap(), &'f');
+    }
+}
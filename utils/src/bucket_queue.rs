@@ -0,0 +1,181 @@
+//! BucketQueue
+//! A min priority queue for small, non negative integer keys, that are
+//! popped in non decreasing order (Dial's algorithm).  Each key gets a
+//! bucket, so push and pop are O(1) amortized, instead of O(log n) for
+//! [crate::PriorityQueue].  Buckets are dropped as the smallest key
+//! advances, so memory is bounded by the spread between the smallest and
+//! largest keys queued at once, e.g. the largest edge cost in Dijkstra.
+//!
+//! Keys must be monotone: pushing a key smaller than the last popped key
+//! panics.
+//!
+//! ```rust
+//! # use utils::BucketQueue;
+//! let mut queue = BucketQueue::<u32, char>::new();
+//! queue.push(3, 'a');
+//! queue.push(1, 'b');
+//! assert_eq!(queue.pop(), Some((1, 'b')));
+//! queue.push(2, 'c');
+//! assert_eq!(queue.pop(), Some((2, 'c')));
+//! assert_eq!(queue.pop(), Some((3, 'a')));
+//! ```
+use std::collections::VecDeque;
+use std::marker::PhantomData;
+
+#[derive(Debug)]
+pub struct BucketQueue<K, V> {
+    /// buckets[i] holds the values with key `base + i`
+    buckets: VecDeque<Vec<V>>,
+    base: usize,
+    /// The last popped key, and so the smallest key that can be pushed
+    floor: usize,
+    len: usize,
+    key: PhantomData<K>,
+}
+
+impl<K: num::PrimInt, V> Default for BucketQueue<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: num::PrimInt, V> BucketQueue<K, V> {
+    pub fn new() -> Self {
+        Self {
+            buckets: VecDeque::new(),
+            base: 0,
+            floor: 0,
+            len: 0,
+            key: PhantomData,
+        }
+    }
+
+    /// `spread` is the expected difference between the smallest and largest
+    /// keys queued at once.
+    pub fn with_capacity(spread: usize) -> Self {
+        Self {
+            buckets: VecDeque::with_capacity(spread + 1),
+            ..Self::new()
+        }
+    }
+
+    pub fn push(&mut self, key: K, value: V) {
+        let key = key.to_usize().expect("BucketQueue keys must fit in usize");
+        assert!(
+            key >= self.floor,
+            "BucketQueue keys must be monotone: {} < {}",
+            key,
+            self.floor
+        );
+        // Buckets only cover the keys queued, so a far away key doesn't
+        // allocate a bucket for every key before it
+        if self.len == 0 {
+            self.buckets.clear();
+            self.base = key;
+        }
+        while key < self.base {
+            self.buckets.push_front(Vec::new());
+            self.base -= 1;
+        }
+        let idx = key - self.base;
+        if idx >= self.buckets.len() {
+            self.buckets.resize_with(idx + 1, Vec::new);
+        }
+        self.buckets[idx].push(value);
+        self.len += 1;
+    }
+
+    pub fn pop(&mut self) -> Option<(K, V)> {
+        if self.len == 0 {
+            return None;
+        }
+        while self.buckets.front().is_some_and(|bucket| bucket.is_empty()) {
+            self.buckets.pop_front();
+            self.base += 1;
+        }
+        let value = self.buckets.front_mut()?.pop()?;
+        self.len -= 1;
+        self.floor = self.base;
+        Some((K::from(self.base).expect("Key was pushed"), value))
+    }
+
+    /// Return the smallest key queued
+    pub fn peek_key(&self) -> Option<K> {
+        self.buckets
+            .iter()
+            .position(|bucket| !bucket.is_empty())
+            .and_then(|idx| K::from(self.base + idx))
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Remove everything.  Keys are monotone from the last popped key, so
+    /// smaller keys still can't be pushed.
+    pub fn clear(&mut self) {
+        self.buckets.clear();
+        self.len = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_it() {
+        let mut queue = BucketQueue::<u32, u32>::with_capacity(9);
+        for value in [5, 1, 9, 1, 3] {
+            queue.push(value, value * 10);
+        }
+        assert_eq!(queue.len(), 5);
+        assert_eq!(queue.peek_key(), Some(1));
+
+        let mut popped = Vec::new();
+        while let Some((key, value)) = queue.pop() {
+            assert_eq!(key * 10, value);
+            // Monotone: pushing the current key is fine
+            if key == 3 && !popped.contains(&3) {
+                queue.push(3, 30);
+                queue.push(12, 120);
+            }
+            popped.push(key);
+        }
+        assert_eq!(popped, vec![1, 1, 3, 3, 5, 9, 12]);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_far_keys() {
+        let mut queue = BucketQueue::<u64, char>::new();
+        queue.push(1_000_000_000, 'a');
+        queue.push(1_000_000_002, 'b');
+        assert_eq!(queue.buckets.len(), 3);
+        // A smaller key, before anything is popped, grows the front
+        queue.push(999_999_999, 'z');
+        assert_eq!(queue.buckets.len(), 4);
+        assert_eq!(queue.pop(), Some((999_999_999, 'z')));
+        assert_eq!(queue.pop(), Some((1_000_000_000, 'a')));
+        assert_eq!(queue.pop(), Some((1_000_000_002, 'b')));
+
+        // Drained, so a far away key starts again
+        queue.push(5_000_000_000, 'c');
+        assert_eq!(queue.buckets.len(), 1);
+        assert_eq!(queue.peek_key(), Some(5_000_000_000));
+        assert_eq!(queue.pop(), Some((5_000_000_000, 'c')));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_not_monotone() {
+        let mut queue = BucketQueue::<u32, ()>::new();
+        queue.push(5, ());
+        queue.pop();
+        queue.push(4, ());
+    }
+}
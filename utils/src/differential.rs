@@ -0,0 +1,255 @@
+//! Differential testing
+//! Runs two [Solution]s of the same puzzle on generated inputs, and when
+//! they disagree, shrinks the input to a small one they still disagree on.
+//! A brute force solution is usually easy to get right, so it makes a good
+//! check on a clever one.
+//!
+//! ```rust
+//! use anyhow::Result;
+//! use utils::{find_disagreement, Rng, Solution};
+//!
+//! struct Sum;
+//! impl Solution for Sum {
+//!     type Input = Vec<u64>;
+//!     type Output = u64;
+//!     fn solve(&self, input: &Vec<u64>) -> Result<u64> {
+//!         Ok(input.iter().sum())
+//!     }
+//! }
+//!
+//! /// Forgets the last value
+//! struct Buggy;
+//! impl Solution for Buggy {
+//!     type Input = Vec<u64>;
+//!     type Output = u64;
+//!     fn solve(&self, input: &Vec<u64>) -> Result<u64> {
+//!         Ok(input.iter().rev().skip(1).sum())
+//!     }
+//! }
+//!
+//! let generate = |rng: &mut Rng| (0..rng.below(10)).map(|_| rng.below(100)).collect();
+//! let found = find_disagreement(&Sum, &Buggy, 100, 1, generate).unwrap();
+//! assert_eq!(found.input, vec![1]);
+//! assert_eq!(found.left.unwrap(), 1);
+//! assert_eq!(found.right.unwrap(), 0);
+//! ```
+use std::fmt::Debug;
+
+use anyhow::Result;
+
+/// A way to solve a puzzle
+pub trait Solution {
+    type Input;
+    type Output: PartialEq + Debug;
+
+    fn solve(&self, input: &Self::Input) -> Result<Self::Output>;
+}
+
+/// Values that can be made smaller.  [Shrink::shrink] gives simpler
+/// candidates to try in place of a value, most aggressive first.
+pub trait Shrink: Sized {
+    fn shrink(&self) -> Vec<Self>;
+}
+
+impl Shrink for u64 {
+    fn shrink(&self) -> Vec<Self> {
+        let mut candidates = vec![0, self / 2, self.saturating_sub(1)];
+        candidates.dedup();
+        candidates.retain(|candidate| candidate < self);
+        candidates
+    }
+}
+
+impl Shrink for usize {
+    fn shrink(&self) -> Vec<Self> {
+        (*self as u64)
+            .shrink()
+            .into_iter()
+            .map(|v| v as usize)
+            .collect()
+    }
+}
+
+/// Remove an element, then shrink an element
+impl<T: Shrink + Clone> Shrink for Vec<T> {
+    fn shrink(&self) -> Vec<Self> {
+        let removed = (0..self.len()).map(|idx| {
+            let mut candidate = self.clone();
+            candidate.remove(idx);
+            candidate
+        });
+        let shrunk = self.iter().enumerate().flat_map(|(idx, item)| {
+            item.shrink().into_iter().map(move |item| {
+                let mut candidate = self.clone();
+                candidate[idx] = item;
+                candidate
+            })
+        });
+        removed.chain(shrunk).collect()
+    }
+}
+
+impl<A: Shrink + Clone, B: Shrink + Clone> Shrink for (A, B) {
+    fn shrink(&self) -> Vec<Self> {
+        let first = self.0.shrink().into_iter().map(|a| (a, self.1.clone()));
+        let second = self.1.shrink().into_iter().map(|b| (self.0.clone(), b));
+        first.chain(second).collect()
+    }
+}
+
+impl<A: Shrink + Clone, B: Shrink + Clone, C: Shrink + Clone> Shrink for (A, B, C) {
+    fn shrink(&self) -> Vec<Self> {
+        ((self.0.clone(), self.1.clone()), self.2.clone())
+            .shrink()
+            .into_iter()
+            .map(|((a, b), c)| (a, b, c))
+            .collect()
+    }
+}
+
+/// A small, seeded random number generator (SplitMix64), so failures can be
+/// reproduced.  Not for anything but tests.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A value in `0..bound`.  `bound` must not be 0.
+    pub fn below(&mut self, bound: u64) -> u64 {
+        self.next_u64() % bound
+    }
+}
+
+/// An input two solutions disagree on, and what each gave
+#[derive(Debug)]
+pub struct Disagreement<I, O> {
+    pub input: I,
+    pub left: Result<O>,
+    pub right: Result<O>,
+}
+
+/// Two results agree if they are equal, or both errors.  Error messages are
+/// free to differ.
+fn agree<O: PartialEq>(left: &Result<O>, right: &Result<O>) -> bool {
+    match (left, right) {
+        (Ok(left), Ok(right)) => left == right,
+        (Err(_), Err(_)) => true,
+        _ => false,
+    }
+}
+
+/// Run `left` and `right` on `cases` inputs from `generate`, seeded with
+/// `seed`.  Returns the first input they disagree on, shrunk until no
+/// smaller candidate still disagrees, or None if they always agree.
+pub fn find_disagreement<L, R, G>(
+    left: &L,
+    right: &R,
+    cases: usize,
+    seed: u64,
+    mut generate: G,
+) -> Option<Disagreement<L::Input, L::Output>>
+where
+    L: Solution,
+    R: Solution<Input = L::Input, Output = L::Output>,
+    L::Input: Shrink + Clone,
+    G: FnMut(&mut Rng) -> L::Input,
+{
+    let mut rng = Rng::new(seed);
+    let disagrees = |input: &L::Input| !agree(&left.solve(input), &right.solve(input));
+
+    let mut input = (0..cases)
+        .map(|_| generate(&mut rng))
+        .find(|input| disagrees(input))?;
+    // Take the first smaller input that still disagrees, until there are none
+    while let Some(smaller) = input
+        .shrink()
+        .into_iter()
+        .find(|candidate| disagrees(candidate))
+    {
+        input = smaller;
+    }
+    Some(Disagreement {
+        left: left.solve(&input),
+        right: right.solve(&input),
+        input,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+
+    struct Max;
+    impl Solution for Max {
+        type Input = Vec<(u64, u64)>;
+        type Output = u64;
+        fn solve(&self, input: &Self::Input) -> Result<u64> {
+            input
+                .iter()
+                .map(|(a, b)| a * b)
+                .max()
+                .ok_or(anyhow!("Empty"))
+        }
+    }
+
+    /// Right, unless a product is over 50
+    struct Capped;
+    impl Solution for Capped {
+        type Input = Vec<(u64, u64)>;
+        type Output = u64;
+        fn solve(&self, input: &Self::Input) -> Result<u64> {
+            match input.is_empty() {
+                true => Err(anyhow!("No input")),
+                false => Ok(input.iter().map(|(a, b)| (a * b).min(50)).max().unwrap()),
+            }
+        }
+    }
+
+    fn generate(rng: &mut Rng) -> Vec<(u64, u64)> {
+        (0..rng.below(8))
+            .map(|_| (rng.below(20), rng.below(20)))
+            .collect()
+    }
+
+    #[test]
+    fn test_shrink() {
+        assert_eq!(10u64.shrink(), vec![0, 5, 9]);
+        assert_eq!(1u64.shrink(), vec![0]);
+        assert!(0u64.shrink().is_empty());
+        assert_eq!(vec![2u64].shrink(), vec![vec![], vec![0], vec![1]]);
+        assert_eq!((1u64, 0u64).shrink(), vec![(0, 0)]);
+    }
+
+    #[test]
+    fn test_disagreement() {
+        assert!(find_disagreement(&Max, &Max, 100, 7, generate).is_none());
+
+        let found = find_disagreement(&Max, &Capped, 100, 7, generate).unwrap();
+        // The smallest input with a product over 50: no element can be
+        // removed, and neither factor made smaller
+        assert_eq!(found.input.len(), 1);
+        let (a, b) = found.input[0];
+        assert!(a * b > 50);
+        assert!((a - 1) * b <= 50 && a * (b - 1) <= 50);
+        assert_eq!(found.left.unwrap(), a * b);
+        assert_eq!(found.right.unwrap(), 50);
+
+        // Same seed, same answer
+        let again = find_disagreement(&Max, &Capped, 100, 7, generate).unwrap();
+        assert_eq!(again.input, found.input);
+    }
+}
@@ -6,3 +6,35 @@ pub enum Direction {
     North,
     South,
 }
+
+impl Direction {
+    /// Returns the direction 90 degrees counter clockwise
+    pub fn turn_left(&self) -> Self {
+        match self {
+            Self::East => Self::North,
+            Self::North => Self::West,
+            Self::West => Self::South,
+            Self::South => Self::East,
+        }
+    }
+
+    /// Returns the direction 90 degrees clockwise
+    pub fn turn_right(&self) -> Self {
+        match self {
+            Self::East => Self::South,
+            Self::South => Self::West,
+            Self::West => Self::North,
+            Self::North => Self::East,
+        }
+    }
+
+    /// Returns the opposite direction
+    pub fn reverse(&self) -> Self {
+        match self {
+            Self::East => Self::West,
+            Self::West => Self::East,
+            Self::North => Self::South,
+            Self::South => Self::North,
+        }
+    }
+}
@@ -1,12 +1,59 @@
-use super::{Direction, Point};
+use super::{Direction, Point, Walker};
 use anyhow::{anyhow, Result};
+use std::marker::PhantomData;
+
+/// The grid API shared by [Grid], [crate::ArrayGrid] and [crate::AnyGrid],
+/// so algorithms can be written once for any of them.  Only cell access by
+/// `x`, `y` has to be implemented.
+pub trait GridLike<T> {
+    fn width(&self) -> usize;
+    fn height(&self) -> usize;
+    fn get_at(&self, x: usize, y: usize) -> Result<&T>;
+    fn get_at_mut(&mut self, x: usize, y: usize) -> Result<&mut T>;
+
+    /// The point of the bottom right cell
+    fn bounds<P: num::FromPrimitive>(&self) -> Point<P>
+    where
+        Self: Sized,
+    {
+        Point::<P>::from((self.width() - 1, self.height() - 1))
+    }
+
+    /// Return a reference to the value at the given point
+    fn get<P: Copy>(&self, point: &Point<P>) -> Result<&T>
+    where
+        Self: Sized,
+        usize: std::convert::TryFrom<P>,
+    {
+        let index = point.indexible()?;
+        self.get_at(index.x, index.y)
+    }
+
+    fn get_mut<P: Copy>(&mut self, point: &Point<P>) -> Result<&mut T>
+    where
+        Self: Sized,
+        usize: std::convert::TryFrom<P>,
+    {
+        let index = point.indexible()?;
+        self.get_at_mut(index.x, index.y)
+    }
+
+    /// Create a [Walker] that starts at `position`, facing `heading`.
+    fn walker<P>(&self, position: Point<P>, heading: Direction) -> Walker<'_, P, T>
+    where
+        Self: Sized,
+        P: std::fmt::Display + num::Integer + num::PrimInt + num::FromPrimitive + Sync + Send,
+        T: std::fmt::Debug + Sized + Send + Sync,
+    {
+        Walker::new(self, position, heading)
+    }
+}
 
 #[derive(Debug)]
 pub struct Grid<P, T> {
     width: usize,
     height: usize,
-    curr: Point<P>,
-    pub direction: Direction,
+    marker: PhantomData<P>,
     pub cells: Vec<T>,
 }
 
@@ -24,14 +71,14 @@ impl<
         if x >= self.width || y >= self.height {
             return Err(anyhow!("Out of bounds: {},{}", x, y));
         }
-        Ok(self.cells.get(y * self.height + x).expect("WTF?"))
+        Ok(self.cells.get(y * self.width + x).expect("WTF?"))
     }
 
     pub fn get_at_mut(&mut self, x: usize, y: usize) -> Result<&mut T> {
         if x >= self.width || y >= self.height {
             return Err(anyhow!("Out of bounds: {},{}", x, y));
         }
-        Ok(self.cells.get_mut(y * self.height + x).expect("WTF?"))
+        Ok(self.cells.get_mut(y * self.width + x).expect("WTF?"))
     }
     /// Return a reference to the value at the given point
     /// If the point can't be used to index, or is not within the
@@ -60,96 +107,111 @@ impl<
         Ok(self.get_at_mut(index.x, index.y).expect("WTF?"))
     }
 
-    pub fn get_curr(&self) -> Result<&T>
-    where
-        usize: std::convert::TryFrom<P>,
-    {
-        self.get(&self.curr)
-    }
-
     pub fn from_cells(cells: Vec<T>, width: usize, height: usize) -> Self {
-        let curr = Point::<P>::origin();
-        let direction = Direction::default();
         Self {
             width,
             height,
+            marker: PhantomData,
             cells,
-            curr,
-            direction,
         }
     }
 
     /// Reduce a new line delimited set of  PATTERN delimited strings
     /// to Vec<T>
+    /// Every line must have the same number of cells.  Trailing blank lines
+    /// are skipped, but a blank line inside the grid is an error.
     /// Example:
     /// ````
+    /// use utils::Grid;
     /// let input = "0,1,2,3,4\n5,6,7,8,9";
     /// let convert = |s: &str| s.parse::<u32>().map_err(|e| e.into());
-    /// let grid = Grid::parse_undelim_str(input,convert).expect("oops");
+    /// let grid = Grid::<i32, u32>::parse_str(input, ",", convert).expect("oops");
+    /// assert_eq!(grid.width(), 5);
     /// ````
     pub fn parse_str<F>(input: &str, pattern: &str, convert: F) -> Result<Self>
     where
         F: Fn(&str) -> Result<T>,
     {
-        let mut cells: Vec<T> = Vec::new();
-        let mut width: usize = 0;
-        let mut height: usize = 0;
-
-        for line in input.lines() {
-            height += 1;
-            width = 0;
-            for s in line.split(pattern) {
-                width += 1;
-                let t = convert(s)?;
-                cells.push(t);
-            }
-        }
-        let curr = Point::<P>::origin();
-        let direction = Direction::default();
-        Ok(Grid {
-            width,
-            height,
-            cells,
-            curr,
-            direction,
-        })
+        let rows = parse_rows(input, |line| line.split(pattern).map(&convert).collect())?;
+        Self::from_rows(rows)
+    }
+
+    /// Lenient version of [Grid::parse_str].  Rows shorter than the widest
+    /// row are padded with `fill`.
+    pub fn parse_str_padded<F>(input: &str, pattern: &str, fill: T, convert: F) -> Result<Self>
+    where
+        F: Fn(&str) -> Result<T>,
+        T: Clone,
+    {
+        let rows = parse_rows(input, |line| line.split(pattern).map(&convert).collect())?;
+        Self::from_rows_padded(rows, fill)
     }
 
     /// Reduce a new line delimited set of chars
     /// to Vec<T>
+    /// Every line must have the same number of cells.  Trailing blank lines
+    /// are skipped, but a blank line inside the grid is an error.
     /// Example:
     /// ````
+    /// use anyhow::anyhow;
+    /// use utils::Grid;
     /// let input = "01234\n56789";
-    /// let convert = |c: char| c.to_digit(10).or(anyhow!("Failed to convert"));
-    /// let grid = Grid::parse_undelim_str(input,convert).expect("oops");
+    /// let convert = |c: char| c.to_digit(10).ok_or(anyhow!("Failed to convert"));
+    /// let grid = Grid::<i32, u32>::parse_undelim_str(input, convert).expect("oops");
+    /// assert_eq!(grid.height(), 2);
     /// ````
     pub fn parse_undelim_str<F>(input: &str, convert: F) -> Result<Self>
     where
         F: Fn(char) -> Result<T>,
     {
-        let mut cells: Vec<T> = Vec::new();
-        let mut width: usize = 0;
-        let mut height: usize = 0;
-
-        for line in input.lines() {
-            height += 1;
-            width = 0;
-            for s in line.chars() {
-                width += 1;
-                let t = convert(s)?;
-                cells.push(t);
+        let rows = parse_rows(input, |line| line.chars().map(&convert).collect())?;
+        Self::from_rows(rows)
+    }
+
+    /// Lenient version of [Grid::parse_undelim_str].  Rows shorter than the
+    /// widest row are padded with `fill`.
+    pub fn parse_undelim_str_padded<F>(input: &str, fill: T, convert: F) -> Result<Self>
+    where
+        F: Fn(char) -> Result<T>,
+        T: Clone,
+    {
+        let rows = parse_rows(input, |line| line.chars().map(&convert).collect())?;
+        Self::from_rows_padded(rows, fill)
+    }
+
+    /// Flatten parsed rows into a grid.  Every row must be as wide as the
+    /// first row.
+    pub(crate) fn from_rows(rows: Vec<Vec<T>>) -> Result<Self> {
+        let height = rows.len();
+        let width = rows.first().map(|row| row.len()).unwrap_or(0);
+
+        let mut cells: Vec<T> = Vec::with_capacity(width * height);
+        for (idx, row) in rows.into_iter().enumerate() {
+            if row.len() != width {
+                return Err(anyhow!(
+                    "Line {}: expected {} cells, found {}",
+                    idx + 1,
+                    width,
+                    row.len()
+                ));
             }
+            cells.extend(row);
         }
-        let curr = Point::<P>::origin();
-        let direction = Direction::default();
-        Ok(Grid {
-            width,
-            height,
-            cells,
-            curr,
-            direction,
-        })
+        Ok(Self::from_cells(cells, width, height))
     }
+
+    /// Pad every row to the width of the widest row, then flatten.
+    fn from_rows_padded(mut rows: Vec<Vec<T>>, fill: T) -> Result<Self>
+    where
+        T: Clone,
+    {
+        let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+        for row in rows.iter_mut() {
+            row.resize(width, fill.clone());
+        }
+        Self::from_rows(rows)
+    }
+
     pub fn width(&self) -> usize {
         self.width
     }
@@ -158,101 +220,179 @@ impl<
         self.height
     }
 
-    /// Copies the current position.
-    pub fn current(&self) -> Point<P> {
-        self.curr
+    /// Return the orthogonal (von Neumann) neighbors of the given point that
+    /// are on the grid, in North, East, South, West order.
+    pub fn neighbors(&self, from: &Point<P>) -> impl Iterator<Item = Point<P>> + '_ {
+        let from = *from;
+        VON_NEUMANN
+            .iter()
+            .filter_map(move |&(dx, dy)| self.offset(&from, dx, dy))
     }
 
-    /// Return all the valid neighbors for the given point
-    pub fn neighbors(&self, from: &Point<P>, max: u32) -> Vec<Point<P>> {
-        let mut result = vec![];
+    /// Return the 8-way (Moore) neighbors of the given point that are on the
+    /// grid, clockwise from North: N, NE, E, SE, S, SW, W, NW.
+    pub fn moore_neighbors(&self, from: &Point<P>) -> impl Iterator<Item = Point<P>> + '_ {
+        let from = *from;
+        MOORE
+            .iter()
+            .filter_map(move |&(dx, dy)| self.offset(&from, dx, dy))
+    }
 
-        let mut tmp_vec: Vec<Point<P>> = Vec::with_capacity(3);
-        let mut tmp = *from;
-        for _ in 0..max {
-            if let Some(next) = self.try_step(&tmp, Direction::North) {
-                tmp_vec.push(next);
-            }
-            tmp = tmp.up();
-        }
-        while let Some(tmp) = tmp_vec.pop() {
-            result.push(tmp);
-        }
+    /// Return the points on the grid at exactly `radius` manhattan distance
+    /// from the given point.  Points are returned clockwise, starting from
+    /// the point due North.  A radius of 0 returns just the given point.
+    pub fn ring(&self, from: &Point<P>, radius: u32) -> impl Iterator<Item = Point<P>> + '_ {
+        let from = *from;
+        let r = radius as i64;
+        let center = (radius == 0).then_some(from);
+        (0..4 * r)
+            .filter_map(move |k| {
+                let i = k % r;
+                let (dx, dy) = match k / r {
+                    0 => (i, i - r),
+                    1 => (r - i, i),
+                    2 => (-i, r - i),
+                    _ => (i - r, -i),
+                };
+                self.offset(&from, dx, dy)
+            })
+            .chain(center)
+    }
 
-        tmp = *from;
-        for _ in 0..max {
-            if let Some(next) = self.try_step(&tmp, Direction::West) {
-                tmp_vec.push(next);
-            }
-            tmp = tmp.left();
-        }
-        while let Some(tmp) = tmp_vec.pop() {
-            result.push(tmp);
-        }
+    /// Return the points from the given point (exclusive) in `direction`,
+    /// until either the edge of the grid is reached or `blocked` returns true
+    /// for a cell.  The blocking cell is not returned.
+    pub fn ray<'a, F>(
+        &'a self,
+        from: &Point<P>,
+        direction: Direction,
+        mut blocked: F,
+    ) -> impl Iterator<Item = Point<P>> + 'a
+    where
+        F: FnMut(&T) -> bool + 'a,
+    {
+        let (dx, dy) = delta(direction);
+        let mut current = Some(*from);
+        std::iter::from_fn(move || {
+            let next = self.offset(&current?, dx, dy);
+            current = next.filter(|point| !blocked(self.cell(point)));
+            current
+        })
+    }
 
-        tmp = *from;
-        for _ in 0..max {
-            if let Some(next) = self.try_step(&tmp, Direction::South) {
-                tmp_vec.push(next);
-            }
-            tmp = tmp.down();
-        }
-        while let Some(tmp) = tmp_vec.pop() {
-            result.push(tmp);
-        }
+    fn offset(&self, from: &Point<P>, dx: i64, dy: i64) -> Option<Point<P>> {
+        offset(self.width, self.height, from, dx, dy)
+    }
 
-        tmp = *from;
-        for _ in 0..max {
-            if let Some(next) = self.try_step(&tmp, Direction::East) {
-                tmp_vec.push(next);
-            }
-            tmp = tmp.right();
-        }
-        while let Some(tmp) = tmp_vec.pop() {
-            result.push(tmp);
-        }
+    /// Return the cell at a point already known to be on the grid.
+    fn cell(&self, point: &Point<P>) -> &T {
+        let x = point.x.to_usize().expect("Point is on the grid");
+        let y = point.y.to_usize().expect("Point is on the grid");
+        &self.cells[y * self.width + x]
+    }
+}
 
-        result
+impl<
+        P: std::fmt::Display + num::Integer + num::PrimInt + num::FromPrimitive + Sync + Send,
+        T: std::fmt::Debug + Sized + Send + Sync,
+    > GridLike<T> for Grid<P, T>
+{
+    fn width(&self) -> usize {
+        self.width
     }
 
-    fn try_step(&self, from: &Point<P>, direction: Direction) -> Option<Point<P>> {
-        let next = from.step(direction);
-        match next.bounded_z(&self.bounds()) {
-            true => Some(next),
-            false => None,
-        }
+    fn height(&self) -> usize {
+        self.height
     }
 
-    pub fn step(&mut self) -> Option<Point<P>> {
-        let next = self.curr.step(self.direction);
-        match next.bounded_z(&self.bounds()) {
-            true => {
-                self.curr = next;
-                Some(next)
-            }
-            false => None,
-        }
+    fn get_at(&self, x: usize, y: usize) -> Result<&T> {
+        Grid::get_at(self, x, y)
     }
 
-    pub fn left(&mut self) -> Option<Point<P>> {
-        self.direction = Direction::West;
-        self.step()
+    fn get_at_mut(&mut self, x: usize, y: usize) -> Result<&mut T> {
+        Grid::get_at_mut(self, x, y)
     }
+}
 
-    pub fn right(&mut self) -> Option<Point<P>> {
-        self.direction = Direction::East;
-        self.step()
+/// Return the point offset from `from` by `dx`, `dy`, if it is on a
+/// `width` x `height` grid.  Works in i64, so unsigned points never
+/// underflow.
+fn offset<P: num::PrimInt + num::FromPrimitive>(
+    width: usize,
+    height: usize,
+    from: &Point<P>,
+    dx: i64,
+    dy: i64,
+) -> Option<Point<P>> {
+    let x = from.x.to_i64()?.checked_add(dx)?;
+    let y = from.y.to_i64()?.checked_add(dy)?;
+    if x < 0 || y < 0 || x >= width as i64 || y >= height as i64 {
+        return None;
     }
+    Some(Point {
+        x: P::from_i64(x)?,
+        y: P::from_i64(y)?,
+    })
+}
+
+/// Return the point one step from `from` in `direction`, if it is on a
+/// `width` x `height` grid.
+pub(crate) fn try_step<P: num::PrimInt + num::FromPrimitive>(
+    width: usize,
+    height: usize,
+    from: &Point<P>,
+    direction: Direction,
+) -> Option<Point<P>> {
+    let (dx, dy) = delta(direction);
+    offset(width, height, from, dx, dy)
+}
+
+/// Orthogonal offsets in N, E, S, W order.
+const VON_NEUMANN: [(i64, i64); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
 
-    pub fn up(&mut self) -> Option<Point<P>> {
-        self.direction = Direction::North;
-        self.step()
+/// All 8 offsets, clockwise from North.
+const MOORE: [(i64, i64); 8] = [
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+];
+
+fn delta(direction: Direction) -> (i64, i64) {
+    match direction {
+        Direction::North => (0, -1),
+        Direction::East => (1, 0),
+        Direction::South => (0, 1),
+        Direction::West => (-1, 0),
     }
+}
+
+/// Split the input into lines and convert each line into a row of cells.
+/// Trailing blank lines are dropped.  Any other blank line, or an input
+/// with no lines at all, is an error.  Line numbers in errors are 1 based.
+pub(crate) fn parse_rows<T, F>(input: &str, mut convert: F) -> Result<Vec<Vec<T>>>
+where
+    F: FnMut(&str) -> Result<Vec<T>>,
+{
+    let lines = input.lines().collect::<Vec<&str>>();
+    let len = lines
+        .iter()
+        .rposition(|line| !line.is_empty())
+        .map(|idx| idx + 1)
+        .ok_or(anyhow!("Empty grid"))?;
 
-    pub fn down(&mut self) -> Option<Point<P>> {
-        self.direction = Direction::South;
-        self.step()
+    let mut rows = Vec::with_capacity(len);
+    for (idx, line) in lines[..len].iter().enumerate() {
+        if line.is_empty() {
+            return Err(anyhow!("Line {}: blank line inside grid", idx + 1));
+        }
+        rows.push(convert(line).map_err(|e| anyhow!("Line {}: {}", idx + 1, e))?);
     }
+    Ok(rows)
 }
 
 #[cfg(test)]
@@ -267,11 +407,99 @@ mod tests {
 6,7,8,9,10"#;
         let result = Grid::<i32, u32>::parse_str(input, pattern, convert);
         assert!(result.is_ok());
-        let mut grid = result.unwrap();
+        let grid = result.unwrap();
         assert_eq!(grid.height(), 2);
         assert_eq!(grid.width(), 5);
-        println!("grid.get_curr(): {:?}", grid.get_curr().unwrap());
-        grid.step();
-        println!("grid.get_curr(): {:?}", grid.get_curr().unwrap());
+        assert_eq!(grid.get_at(4, 1).unwrap(), &10);
+        assert_eq!(grid.get(&Point::new(1, 1)).unwrap(), &7);
+    }
+
+    #[test]
+    fn test_ragged() {
+        let convert = |c: char| Ok(c);
+        let result = Grid::<i32, char>::parse_undelim_str("abc\nab\nabc", convert);
+        let err = result.expect_err("ragged input should fail");
+        assert!(err.to_string().starts_with("Line 2:"));
+
+        let grid = Grid::<i32, char>::parse_undelim_str_padded("abc\nab\nabc", '.', convert)
+            .expect("padded");
+        assert_eq!(grid.width(), 3);
+        assert_eq!(grid.height(), 3);
+        assert_eq!(grid.get_at(2, 1).unwrap(), &'.');
+    }
+
+    #[test]
+    fn test_blank_lines() {
+        let convert = |c: char| Ok(c);
+        let grid = Grid::<i32, char>::parse_undelim_str("ab\r\ncd\n\n", convert).expect("trailing");
+        assert_eq!(grid.height(), 2);
+        assert_eq!(grid.get_at(1, 1).unwrap(), &'d');
+
+        assert!(Grid::<i32, char>::parse_undelim_str("ab\n\ncd", convert).is_err());
+        assert!(Grid::<i32, char>::parse_undelim_str("\n\n", convert).is_err());
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let grid = Grid::<u32, char>::parse_undelim_str("abc\ndef\nghi", Ok).unwrap();
+        let corner = Point::<u32>::origin();
+        let center = Point::<u32>::new(1, 1);
+
+        let n = grid.neighbors(&corner).collect::<Vec<_>>();
+        assert_eq!(n, vec![Point::new(1, 0), Point::new(0, 1)]);
+
+        let n = grid.neighbors(&center).collect::<Vec<_>>();
+        assert_eq!(
+            n,
+            vec![
+                Point::new(1, 0),
+                Point::new(2, 1),
+                Point::new(1, 2),
+                Point::new(0, 1)
+            ]
+        );
+
+        let n = grid.moore_neighbors(&corner).collect::<Vec<_>>();
+        assert_eq!(
+            n,
+            vec![Point::new(1, 0), Point::new(1, 1), Point::new(0, 1)]
+        );
+        assert_eq!(grid.moore_neighbors(&center).count(), 8);
+    }
+
+    #[test]
+    fn test_ring() {
+        let grid = Grid::<i32, char>::parse_undelim_str("abcde\nfghij\nklmno", Ok).unwrap();
+        let from = Point::new(2, 1);
+        assert_eq!(grid.ring(&from, 0).collect::<Vec<_>>(), vec![from]);
+        assert_eq!(
+            grid.ring(&from, 1).collect::<Vec<_>>(),
+            grid.neighbors(&from).collect::<Vec<_>>()
+        );
+        let ring = grid.ring(&from, 2).collect::<Vec<_>>();
+        assert_eq!(
+            ring,
+            vec![
+                Point::new(3, 0),
+                Point::new(4, 1),
+                Point::new(3, 2),
+                Point::new(1, 2),
+                Point::new(0, 1),
+                Point::new(1, 0)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ray() {
+        let grid = Grid::<i32, char>::parse_undelim_str("..#..", Ok).unwrap();
+        let from = Point::origin();
+        let ray = grid.ray(&from, Direction::East, |c| *c == '#');
+        assert_eq!(ray.collect::<Vec<_>>(), vec![Point::new(1, 0)]);
+
+        let from = Point::new(3, 0);
+        let ray = grid.ray(&from, Direction::East, |c| *c == '#');
+        assert_eq!(ray.collect::<Vec<_>>(), vec![Point::new(4, 0)]);
+        assert_eq!(grid.ray(&from, Direction::North, |_| false).count(), 0);
     }
 }
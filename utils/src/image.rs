@@ -0,0 +1,209 @@
+//! Image export
+//! Write a [Grid] as a binary PGM (greyscale) or PPM (color) image, one
+//! pixel per cell, or as an SVG with optional path and point overlays.
+//! Any image viewer can open the PGM/PPM output, and browsers open the SVG.
+//!
+//! ```rust
+//! # use utils::{Grid, Overlay, Point};
+//! let grid = Grid::<i32, char>::parse_undelim_str("#.\n.#", Ok).unwrap();
+//! let mut ppm: Vec<u8> = Vec::new();
+//! grid.write_ppm(&mut ppm, |c| match c {
+//!     '#' => [0, 0, 0],
+//!     _ => [255, 255, 255],
+//! })
+//! .unwrap();
+//! assert!(ppm.starts_with(b"P6\n2 2\n255\n"));
+//!
+//! let path = [Point::new(0, 0), Point::new(1, 0), Point::new(1, 1)];
+//! let mut svg: Vec<u8> = Vec::new();
+//! grid.write_svg(&mut svg, 10, |_| [255, 255, 255], &[Overlay::Path(&path, [255, 0, 0])])
+//!     .unwrap();
+//! ```
+use super::{Grid, Point};
+use anyhow::Result;
+use std::io::Write;
+
+/// Red, green, blue
+pub type Rgb = [u8; 3];
+
+/// Extra shapes drawn over an SVG grid, in cell coordinates.
+#[derive(Debug, Clone, Copy)]
+pub enum Overlay<'a, P> {
+    /// A line through the centers of the points, in order
+    Path(&'a [Point<P>], Rgb),
+    /// A dot in the center of each point
+    Points(&'a [Point<P>], Rgb),
+}
+
+impl<
+        P: std::fmt::Display + num::Integer + num::PrimInt + num::FromPrimitive + Sync + Send,
+        T: std::fmt::Debug + Sized + Send + Sync,
+    > Grid<P, T>
+{
+    /// Write the grid as a binary PGM, using `shade` to turn each cell into
+    /// a grey level (0 is black).
+    pub fn write_pgm<W, F>(&self, out: &mut W, shade: F) -> Result<()>
+    where
+        W: Write,
+        F: Fn(&T) -> u8,
+    {
+        write!(out, "P5\n{} {}\n255\n", self.width(), self.height())?;
+        let pixels = self.cells.iter().map(shade).collect::<Vec<u8>>();
+        out.write_all(&pixels)?;
+        Ok(())
+    }
+
+    /// Write the grid as a binary PPM, using `color` to turn each cell into
+    /// a pixel.
+    pub fn write_ppm<W, F>(&self, out: &mut W, color: F) -> Result<()>
+    where
+        W: Write,
+        F: Fn(&T) -> Rgb,
+    {
+        write!(out, "P6\n{} {}\n255\n", self.width(), self.height())?;
+        let pixels = self.cells.iter().flat_map(color).collect::<Vec<u8>>();
+        out.write_all(&pixels)?;
+        Ok(())
+    }
+
+    /// Write the grid as an SVG, with each cell drawn as a `scale` pixel
+    /// square filled by `color`.  Runs of the same color in a row are merged
+    /// into one rectangle to keep the file small.  The overlays are drawn
+    /// on top, in order.
+    pub fn write_svg<W, F>(
+        &self,
+        out: &mut W,
+        scale: usize,
+        color: F,
+        overlays: &[Overlay<P>],
+    ) -> Result<()>
+    where
+        W: Write,
+        F: Fn(&T) -> Rgb,
+    {
+        writeln!(
+            out,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" shape-rendering="crispEdges">"#,
+            self.width() * scale,
+            self.height() * scale
+        )?;
+
+        for (y, row) in self.cells.chunks(self.width().max(1)).enumerate() {
+            let mut x = 0;
+            while x < row.len() {
+                let fill = color(&row[x]);
+                let run = row[x..]
+                    .iter()
+                    .take_while(|cell| color(cell) == fill)
+                    .count();
+                writeln!(
+                    out,
+                    r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}"/>"#,
+                    x * scale,
+                    y * scale,
+                    run * scale,
+                    scale,
+                    hex(fill)
+                )?;
+                x += run;
+            }
+        }
+
+        let center = |point: &Point<P>| -> (f64, f64) {
+            let x = point.x.to_f64().unwrap_or_default();
+            let y = point.y.to_f64().unwrap_or_default();
+            ((x + 0.5) * scale as f64, (y + 0.5) * scale as f64)
+        };
+        for overlay in overlays {
+            match overlay {
+                Overlay::Path(points, stroke) => {
+                    let points = points
+                        .iter()
+                        .map(|point| {
+                            let (x, y) = center(point);
+                            format!("{},{}", x, y)
+                        })
+                        .collect::<Vec<String>>();
+                    writeln!(
+                        out,
+                        r#"<polyline points="{}" fill="none" stroke="{}" stroke-width="{}"/>"#,
+                        points.join(" "),
+                        hex(*stroke),
+                        (scale as f64 / 4.0).max(1.0)
+                    )?;
+                }
+                Overlay::Points(points, fill) => {
+                    for point in points.iter() {
+                        let (x, y) = center(point);
+                        writeln!(
+                            out,
+                            r#"<circle cx="{}" cy="{}" r="{}" fill="{}"/>"#,
+                            x,
+                            y,
+                            scale as f64 / 3.0,
+                            hex(*fill)
+                        )?;
+                    }
+                }
+            }
+        }
+        writeln!(out, "</svg>")?;
+        Ok(())
+    }
+}
+
+fn hex(color: Rgb) -> String {
+    format!("#{:02x}{:02x}{:02x}", color[0], color[1], color[2])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid() -> Grid<i32, u8> {
+        Grid::parse_undelim_str("012\n345", |c| Ok(c as u8 - b'0')).unwrap()
+    }
+
+    #[test]
+    fn test_netpbm() {
+        let grid = grid();
+        let mut out: Vec<u8> = Vec::new();
+        grid.write_pgm(&mut out, |v| v * 50).unwrap();
+        assert_eq!(&out[..11], b"P5\n3 2\n255\n");
+        assert_eq!(&out[11..], &[0, 50, 100, 150, 200, 250]);
+
+        let mut out: Vec<u8> = Vec::new();
+        grid.write_ppm(&mut out, |v| [*v, 0, 255]).unwrap();
+        assert_eq!(&out[..11], b"P6\n3 2\n255\n");
+        assert_eq!(out.len(), 11 + 3 * 6);
+        assert_eq!(&out[14..17], &[1, 0, 255]);
+    }
+
+    #[test]
+    fn test_svg() {
+        let grid = grid();
+        let path = [Point::new(0, 0), Point::new(2, 1)];
+        let mut out: Vec<u8> = Vec::new();
+        let color = |v: &u8| if *v < 4 { [0, 0, 0] } else { [255, 255, 255] };
+        grid.write_svg(
+            &mut out,
+            10,
+            color,
+            &[
+                Overlay::Path(&path, [255, 0, 0]),
+                Overlay::Points(&path[1..], [0, 255, 0]),
+            ],
+        )
+        .unwrap();
+        let svg = String::from_utf8(out).unwrap();
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.trim_end().ends_with("</svg>"));
+        // The first row is one run, the second row is two
+        assert_eq!(svg.matches("<rect").count(), 3);
+        assert!(svg.contains(r##"<rect x="0" y="0" width="30" height="10" fill="#000000"/>"##));
+        assert!(svg.contains(r#"points="5,5 25,15""#));
+        assert!(
+            svg.contains(r##"<circle cx="25" cy="15" r="3.3333333333333335" fill="#00ff00"/>"##)
+        );
+    }
+}
@@ -0,0 +1,244 @@
+//! IndexedPriorityQueue
+//! A binary heap of key, value pairs that also tracks where each value is in
+//! the heap.  Each value appears at most once, so its key can be looked up,
+//! improved or removed in place.  For Dijkstra and A*, this keeps the
+//! frontier to one entry per node instead of pushing duplicates and
+//! skipping stale ones.
+//!
+//! ```rust
+//! # use utils::IndexedPriorityQueue;
+//! let mut frontier = IndexedPriorityQueue::<u32, char>::new();
+//! frontier.push_or_update(5, 'a');
+//! frontier.push_or_update(3, 'b');
+//! frontier.push_or_update(1, 'a');
+//! assert_eq!(frontier.len(), 2);
+//! assert_eq!(frontier.priority_of(&'a'), Some(&1));
+//! assert_eq!(frontier.pop(), Some((1, 'a')));
+//! ```
+use super::{Min, QueueOrder};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+/// The ordering is chosen with [crate::Min] (the default) or [crate::Max],
+/// as for [crate::PriorityQueue].
+#[derive(Debug)]
+pub struct IndexedPriorityQueue<K: Ord, V: Hash + Eq + Clone, O: QueueOrder = Min> {
+    heap: Vec<(K, V)>,
+    positions: HashMap<V, usize>,
+    order: PhantomData<O>,
+}
+
+impl<K: Ord, V: Hash + Eq + Clone, O: QueueOrder> Default for IndexedPriorityQueue<K, V, O> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Ord, V: Hash + Eq + Clone, O: QueueOrder> IndexedPriorityQueue<K, V, O> {
+    pub fn new() -> Self {
+        Self {
+            heap: Vec::new(),
+            positions: HashMap::new(),
+            order: PhantomData,
+        }
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            heap: Vec::with_capacity(capacity),
+            positions: HashMap::with_capacity(capacity),
+            order: PhantomData,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    pub fn contains(&self, value: &V) -> bool {
+        self.positions.contains_key(value)
+    }
+
+    /// Return the key currently queued for `value`
+    pub fn priority_of(&self, value: &V) -> Option<&K> {
+        self.positions.get(value).map(|&idx| &self.heap[idx].0)
+    }
+
+    /// Return the entry that would be popped next
+    pub fn peek(&self) -> Option<(&K, &V)> {
+        self.heap.first().map(|(key, value)| (key, value))
+    }
+
+    /// Queue `value` with `key`.  If `value` is already queued, its key is
+    /// replaced, whether or not the new key is better.
+    pub fn push(&mut self, key: K, value: V) {
+        match self.positions.get(&value) {
+            Some(&idx) => {
+                self.heap[idx].0 = key;
+                self.sift_up(idx);
+                self.sift_down(idx);
+            }
+            None => {
+                let idx = self.heap.len();
+                self.positions.insert(value.clone(), idx);
+                self.heap.push((key, value));
+                self.sift_up(idx);
+            }
+        }
+    }
+
+    /// Queue `value` with `key` if it isn't queued, or move it up if `key`
+    /// is better (smaller for a min queue, larger for a max queue) than its
+    /// current key.  Returns true if the queue changed.
+    pub fn push_or_update(&mut self, key: K, value: V) -> bool {
+        match self.positions.contains_key(&value) {
+            true => self.decrease_key(&value, key),
+            false => {
+                self.push(key, value);
+                true
+            }
+        }
+    }
+
+    /// Move a queued `value` up to `key`, if `key` is better than its current
+    /// key.  Returns false if `value` isn't queued or `key` isn't better.
+    pub fn decrease_key(&mut self, value: &V, key: K) -> bool {
+        let Some(&idx) = self.positions.get(value) else {
+            return false;
+        };
+        if !self.before(&key, &self.heap[idx].0) {
+            return false;
+        }
+        self.heap[idx].0 = key;
+        self.sift_up(idx);
+        true
+    }
+
+    pub fn pop(&mut self) -> Option<(K, V)> {
+        if self.heap.is_empty() {
+            return None;
+        }
+        Some(self.remove_at(0))
+    }
+
+    /// Remove `value` from the queue, returning its key
+    pub fn remove(&mut self, value: &V) -> Option<K> {
+        let idx = *self.positions.get(value)?;
+        Some(self.remove_at(idx).0)
+    }
+
+    pub fn clear(&mut self) {
+        self.heap.clear();
+        self.positions.clear();
+    }
+
+    fn remove_at(&mut self, idx: usize) -> (K, V) {
+        let last = self.heap.len() - 1;
+        self.swap(idx, last);
+        let (key, value) = self.heap.pop().expect("Not empty");
+        self.positions.remove(&value);
+        if idx < self.heap.len() {
+            self.sift_up(idx);
+            self.sift_down(idx);
+        }
+        (key, value)
+    }
+
+    /// True if `a` should come out of the queue before `b`
+    #[inline]
+    fn before(&self, a: &K, b: &K) -> bool {
+        O::cmp(a, b) == Ordering::Greater
+    }
+
+    fn swap(&mut self, a: usize, b: usize) {
+        if a == b {
+            return;
+        }
+        self.heap.swap(a, b);
+        *self.positions.get_mut(&self.heap[a].1).expect("Tracked") = a;
+        *self.positions.get_mut(&self.heap[b].1).expect("Tracked") = b;
+    }
+
+    fn sift_up(&mut self, mut idx: usize) {
+        while idx > 0 {
+            let parent = (idx - 1) / 2;
+            if !self.before(&self.heap[idx].0, &self.heap[parent].0) {
+                break;
+            }
+            self.swap(idx, parent);
+            idx = parent;
+        }
+    }
+
+    fn sift_down(&mut self, mut idx: usize) {
+        loop {
+            let left = 2 * idx + 1;
+            let right = left + 1;
+            let mut best = idx;
+            if left < self.heap.len() && self.before(&self.heap[left].0, &self.heap[best].0) {
+                best = left;
+            }
+            if right < self.heap.len() && self.before(&self.heap[right].0, &self.heap[best].0) {
+                best = right;
+            }
+            if best == idx {
+                break;
+            }
+            self.swap(idx, best);
+            idx = best;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Max;
+
+    #[test]
+    fn test_decrease_key() {
+        let mut queue = IndexedPriorityQueue::<u32, &str>::new();
+        assert!(queue.push_or_update(10, "a"));
+        assert!(queue.push_or_update(20, "b"));
+        assert!(queue.push_or_update(30, "c"));
+
+        // Only a better key changes the queue
+        assert!(!queue.push_or_update(40, "a"));
+        assert!(queue.push_or_update(5, "c"));
+        assert!(!queue.decrease_key(&"d", 1));
+        assert_eq!(queue.len(), 3);
+        assert_eq!(queue.priority_of(&"c"), Some(&5));
+        assert_eq!(queue.peek(), Some((&5, &"c")));
+
+        // push always replaces
+        queue.push(50, "c");
+        assert_eq!(queue.pop(), Some((10, "a")));
+        assert_eq!(queue.pop(), Some((20, "b")));
+        assert_eq!(queue.pop(), Some((50, "c")));
+        assert_eq!(queue.pop(), None);
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut queue = IndexedPriorityQueue::<u32, u32, Max>::new();
+        for value in 0..10 {
+            queue.push(value * 7 % 10, value);
+        }
+        assert_eq!(queue.remove(&3), Some(1));
+        assert_eq!(queue.remove(&3), None);
+        assert!(!queue.contains(&3));
+        assert!(queue.contains(&4));
+
+        let mut keys = Vec::new();
+        while let Some((key, _)) = queue.pop() {
+            keys.push(key);
+        }
+        assert_eq!(keys, vec![9, 8, 7, 6, 5, 4, 3, 2, 0]);
+    }
+}
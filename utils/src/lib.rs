@@ -1,11 +1,35 @@
+// So the code generated by AocParse also works inside this crate
+extern crate self as utils;
+
+pub use aoc_derive::AocParse;
+pub use array_grid::*;
+pub use bucket_queue::*;
+pub use differential::*;
 pub use direction::*;
 pub use grid::*;
+pub use image::*;
+pub use indexed_priority_queue::*;
+pub use line_parser::*;
 pub use parse::*;
 pub use point::*;
 pub use priority_queue::*;
+pub use search::*;
+pub use tiled::*;
+pub use view::*;
+pub use walker::*;
 
+pub mod array_grid;
+pub mod bucket_queue;
+pub mod differential;
 pub mod direction;
 pub mod grid;
+pub mod image;
+pub mod indexed_priority_queue;
+pub mod line_parser;
 pub mod parse;
 pub mod point;
 pub mod priority_queue;
+pub mod search;
+pub mod tiled;
+pub mod view;
+pub mod walker;
@@ -0,0 +1,212 @@
+//! LineParser
+//! Walks a line of puzzle input, matching literal text and taking the text
+//! of each field.  This is what `#[derive(AocParse)]` generates code
+//! against, but it can be used by hand too.  Errors give the type being
+//! parsed and the column (1 based) where parsing failed.
+//!
+//! ```rust
+//! # use utils::AocParse;
+//! #[derive(Debug, AocParse)]
+//! #[aoc("Card {id}: {winners: ws} | {haves: ws}")]
+//! struct Card {
+//!     id: usize,
+//!     winners: Vec<usize>,
+//!     haves: Vec<usize>,
+//! }
+//!
+//! let card: Card = "Card   1: 41 48 | 83 86  6".parse().unwrap();
+//! assert_eq!(card.id, 1);
+//! assert_eq!(card.haves, vec![83, 86, 6]);
+//!
+//! let err = "Card 1: 41 4x | 83".parse::<Card>().unwrap_err();
+//! assert_eq!(
+//!     err.to_string(),
+//!     "Card: column 12: invalid winners \"4x\": invalid digit found in string"
+//! );
+//! ```
+use std::fmt::Display;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Result};
+
+#[derive(Debug, Clone)]
+pub struct LineParser<'a> {
+    name: &'static str,
+    line: &'a str,
+    pos: usize,
+}
+
+impl<'a> LineParser<'a> {
+    /// `name` is the type being parsed, for error messages.  Whitespace
+    /// around `line` is ignored.
+    pub fn new(name: &'static str, line: &'a str) -> Self {
+        let pos = line.len() - line.trim_start().len();
+        Self { name, line, pos }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.line[self.pos..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        self.pos = self.line.len() - self.rest().trim_start().len();
+    }
+
+    /// 1 based column of `text`, which must be a slice of the line
+    fn column(&self, text: &str) -> usize {
+        let offset = text.as_ptr() as usize - self.line.as_ptr() as usize;
+        self.line[..offset].chars().count() + 1
+    }
+
+    fn error(&self, at: &str, msg: impl Display) -> anyhow::Error {
+        anyhow!("{}: column {}: {}", self.name, self.column(at), msg)
+    }
+
+    /// Match `literal`.  Whitespace in `literal` matches any amount of
+    /// whitespace, including none.
+    pub fn literal(&mut self, literal: &str) -> Result<()> {
+        for c in literal.chars() {
+            if c.is_whitespace() {
+                self.skip_whitespace();
+            } else if self.rest().starts_with(c) {
+                self.pos += c.len_utf8();
+            } else {
+                let rest = self.rest();
+                let found = match rest.is_empty() {
+                    true => "end of line".to_string(),
+                    false => format!("{:?}", rest.chars().take(12).collect::<String>()),
+                };
+                return Err(self.error(
+                    rest,
+                    format!("expected {:?}, found {}", literal.trim(), found),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Take the text up to the start of `until`, or to the end of the line.
+    /// If `until` is only whitespace, stop at the next whitespace.  The text
+    /// is trimmed.
+    pub fn take(&mut self, until: Option<&str>) -> &'a str {
+        self.skip_whitespace();
+        let rest = self.rest();
+        let end = match until.map(|until| until.split_whitespace().next()) {
+            None => rest.len(),
+            Some(None) => rest.find(char::is_whitespace).unwrap_or(rest.len()),
+            Some(Some(stop)) => rest.find(stop).unwrap_or(rest.len()),
+        };
+        self.pos += end;
+        rest[..end].trim_end()
+    }
+
+    /// Parse `text`, taken from this line, as the value of `field`
+    pub fn value<T>(&self, field: &str, text: &'a str) -> Result<T>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        text.parse::<T>()
+            .map_err(|e| self.error(text, format!("invalid {} {:?}: {}", field, text, e)))
+    }
+
+    /// Split `text` on `sep`, or on whitespace if `sep` is None, and parse
+    /// each part as an item of `field`.
+    pub fn list<T, C>(&self, field: &str, text: &'a str, sep: Option<&str>) -> Result<C>
+    where
+        T: FromStr,
+        T::Err: Display,
+        C: FromIterator<T>,
+    {
+        match sep {
+            None => text
+                .split_whitespace()
+                .map(|item| self.value(field, item))
+                .collect(),
+            Some(sep) => text
+                .split(sep)
+                .map(|item| self.value(field, item.trim()))
+                .collect(),
+        }
+    }
+
+    /// Check that nothing but whitespace is left
+    pub fn finish(&self) -> Result<()> {
+        let rest = self.rest().trim();
+        match rest.is_empty() {
+            true => Ok(()),
+            false => Err(self.error(rest, format!("unexpected {:?}", rest))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::AocParse;
+    use std::collections::HashSet;
+
+    #[derive(Debug, PartialEq, AocParse)]
+    #[aoc("{count} {color}")]
+    struct Cubes {
+        count: usize,
+        color: String,
+    }
+
+    #[derive(Debug, PartialEq, AocParse)]
+    #[aoc("Game {id}: {cubes: sep(,)} [{tags: ws}]")]
+    struct Game {
+        id: u32,
+        cubes: Vec<Cubes>,
+        tags: HashSet<String>,
+        #[aoc(default)]
+        score: u32,
+    }
+
+    #[test]
+    fn test_derive() {
+        let game: Game = "  Game 12:  3 blue, 4 red [a b a] ".parse().unwrap();
+        assert_eq!(game.id, 12);
+        assert_eq!(
+            game.cubes,
+            vec![
+                Cubes {
+                    count: 3,
+                    color: "blue".to_string()
+                },
+                Cubes {
+                    count: 4,
+                    color: "red".to_string()
+                }
+            ]
+        );
+        assert_eq!(game.tags.len(), 2);
+        assert_eq!(game.score, 0);
+    }
+
+    #[test]
+    fn test_errors() {
+        let err = |line: &str| line.parse::<Game>().unwrap_err().to_string();
+        assert_eq!(
+            err("Gme 1: 3 red []"),
+            "Game: column 2: expected \"Game\", found \"me 1: 3 red \""
+        );
+        assert_eq!(
+            err("Game 1: 3 red"),
+            "Game: column 14: expected \"[\", found end of line"
+        );
+        assert_eq!(
+            err("Game 1: 3 red [] x"),
+            "Game: column 18: unexpected \"x\""
+        );
+        assert_eq!(
+            err("Game -1: 3 red []"),
+            "Game: column 6: invalid id \"-1\": invalid digit found in string"
+        );
+        // Nested errors are relative to the nested text
+        assert_eq!(
+            err("Game 1: 3 red, x red []"),
+            "Game: column 16: invalid cubes \"x red\": \
+            Cubes: column 1: invalid count \"x\": invalid digit found in string"
+        );
+    }
+}
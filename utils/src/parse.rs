@@ -1,3 +1,4 @@
+use std::marker::PhantomData;
 use std::str::FromStr;
 
 use anyhow::{anyhow, Result};
@@ -16,6 +17,143 @@ pub fn parse_nums<T: FromStr>(input: &str, delim: char) -> Result<Vec<T>> {
     Ok(result)
 }
 
+/// Pull every integer out of `text`, ignoring anything that isn't part of a
+/// number.  A `-` directly before a digit is a sign if `T` is signed, and
+/// not after another digit, so `3-5` is two numbers.  Numbers that don't fit
+/// in `T` are returned as errors.  Nothing is allocated.
+///
+/// ```rust
+/// # use utils::numbers;
+/// let nums = numbers::<i32>("Card 12: x=-3, y=+4 (1-5)");
+/// assert_eq!(nums.collect::<anyhow::Result<Vec<_>>>().unwrap(), vec![12, -3, 4, 1, 5]);
+///
+/// let nums = numbers::<u8>("-3 255 256");
+/// assert_eq!(nums.map(|n| n.ok()).collect::<Vec<_>>(), vec![Some(3), Some(255), None]);
+/// ```
+pub fn numbers<T: FromStr + num::PrimInt>(text: &str) -> Numbers<'_, T> {
+    Numbers {
+        text,
+        pos: 0,
+        marker: PhantomData,
+    }
+}
+
+/// Iterator returned by [numbers]
+#[derive(Debug, Clone)]
+pub struct Numbers<'a, T> {
+    text: &'a str,
+    pos: usize,
+    marker: PhantomData<T>,
+}
+
+impl<'a, T: FromStr + num::PrimInt> Iterator for Numbers<'a, T> {
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let bytes = self.text.as_bytes();
+        let signed = T::min_value() < T::zero();
+
+        let digit = self.pos + bytes[self.pos..].iter().position(u8::is_ascii_digit)?;
+        let negative = signed
+            && digit > 0
+            && bytes[digit - 1] == b'-'
+            && (digit < 2 || !bytes[digit - 2].is_ascii_digit());
+        let start = if negative { digit - 1 } else { digit };
+        let end = digit
+            + bytes[digit..]
+                .iter()
+                .position(|b| !b.is_ascii_digit())
+                .unwrap_or(bytes.len() - digit);
+        self.pos = end;
+
+        let number = &self.text[start..end];
+        Some(
+            number
+                .parse()
+                .map_err(|_| anyhow!("Number out of range: {}", number)),
+        )
+    }
+}
+
+/// Split `input` into blocks of lines separated by blank lines.  Runs of
+/// blank lines, and blank lines at the start or end, are skipped.  A line is
+/// blank if it is only whitespace, so `\r\n` line endings work too.  Each
+/// block is a slice of `input`, without its trailing line ending.
+///
+/// Use [Sections::with_headers] to split the first line off each block.
+///
+/// ```rust
+/// # use utils::sections;
+/// let input = "a:\r\n1\r\n2\r\n\r\nb:\r\n3\r\n\r\n";
+/// let blocks = sections(input).collect::<Vec<_>>();
+/// assert_eq!(blocks, vec!["a:\r\n1\r\n2", "b:\r\n3"]);
+///
+/// let (header, body) = sections(input).with_headers().next().unwrap();
+/// assert_eq!(header, "a");
+/// assert_eq!(body.lines().collect::<Vec<_>>(), vec!["1", "2"]);
+/// ```
+pub fn sections(input: &str) -> Sections<'_> {
+    Sections { rest: input }
+}
+
+/// Iterator returned by [sections]
+#[derive(Debug, Clone)]
+pub struct Sections<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Sections<'a> {
+    /// Yield `(header, body)` for each block, where `header` is the first
+    /// line with any trailing `:` removed, and `body` is the rest of the
+    /// block, which may be empty.
+    pub fn with_headers(self) -> impl Iterator<Item = (&'a str, &'a str)> {
+        self.map(|block| {
+            let (header, body) = block.split_once('\n').unwrap_or((block, ""));
+            let header = header.trim_end_matches('\r').trim_end_matches(':');
+            (header, body)
+        })
+    }
+
+    /// Return the next line, and the rest of the input after it
+    fn split_line(text: &str) -> (&str, &str) {
+        match text.split_once('\n') {
+            Some((line, rest)) => (line.trim_end_matches('\r'), rest),
+            None => (text, ""),
+        }
+    }
+}
+
+impl<'a> Iterator for Sections<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Skip blank lines before the block
+        loop {
+            if self.rest.is_empty() {
+                return None;
+            }
+            let (line, rest) = Self::split_line(self.rest);
+            if !line.trim().is_empty() {
+                break;
+            }
+            self.rest = rest;
+        }
+
+        let block = self.rest;
+        let mut len = 0;
+        while !self.rest.is_empty() {
+            let (line, rest) = Self::split_line(self.rest);
+            if line.trim().is_empty() {
+                break;
+            }
+            // Offset of the end of this line within the block
+            len = block.len() - self.rest.len() + line.len();
+            self.rest = rest;
+        }
+        Some(&block[..len])
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -28,4 +166,50 @@ mod tests {
         let result = parse_nums::<i32>(nums, ',').expect("oops!");
         assert_eq!(&result, &expected);
     }
+
+    #[test]
+    fn test_numbers() {
+        let line = "Card   1: 41 48 | 83 -86\n";
+        let nums = numbers::<usize>(line).collect::<Result<Vec<_>>>().unwrap();
+        assert_eq!(nums, vec![1, 41, 48, 83, 86]);
+        let nums = numbers::<i64>(line).collect::<Result<Vec<_>>>().unwrap();
+        assert_eq!(nums, vec![1, 41, 48, 83, -86]);
+
+        // A dash after a digit is a separator, not a sign
+        let nums = numbers::<i32>("10-20--3")
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(nums, vec![10, 20, -3]);
+
+        assert_eq!(numbers::<u32>("no numbers - here").count(), 0);
+        let err = numbers::<i8>("1 -129").nth(1).unwrap().unwrap_err();
+        assert_eq!(err.to_string(), "Number out of range: -129");
+    }
+
+    #[test]
+    fn test_sections() {
+        let input = "\n\nseeds: 1 2\n\nmap:\n1 2 3\n4 5 6\n\n\n  \nlast\n";
+        let blocks = sections(input).collect::<Vec<_>>();
+        assert_eq!(blocks, vec!["seeds: 1 2", "map:\n1 2 3\n4 5 6", "last"]);
+
+        let headed = sections(input).with_headers().collect::<Vec<_>>();
+        assert_eq!(
+            headed,
+            vec![("seeds: 1 2", ""), ("map", "1 2 3\n4 5 6"), ("last", "")]
+        );
+
+        // CRLF gives the same lines
+        let crlf = input.replace('\n', "\r\n");
+        let lines = sections(&crlf)
+            .map(|block| block.lines().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let expected = blocks
+            .iter()
+            .map(|block| block.lines().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert_eq!(lines, expected);
+
+        assert_eq!(sections("").count(), 0);
+        assert_eq!(sections("\r\n\n").count(), 0);
+    }
 }
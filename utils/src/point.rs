@@ -6,6 +6,7 @@
 //! Typical aritimetic is supported
 //!
 //! ```rust
+//! # use utils::Point;
 //! let lhs = Point::<i32>::new(1,1);
 //! let rhs = Point::<i32>::new(2,2);
 //! let result = lhs + rhs;
@@ -15,6 +16,7 @@
 //! Test whether a point is bounded
 //!
 //! ```rust
+//! # use utils::Point;
 //! let upper = Point::<i32>::origin();
 //! let lower = Point::<i32>::new(10,10);
 //! let inside = Point::<i32>::new(4,5);
@@ -24,23 +26,29 @@
 //! The above can be simplified with [bounded_z]
 //!
 //! ```rust
+//! # use utils::Point;
 //! let lower = Point::<i32>::new(10,10);
 //! let inside = Point::<i32>::new(4,5);
 //! assert!(inside.bounded_z(&lower));
+//! ```
 //!
 //! Find the distance between 2 points:
 //! ```rust
+//! # use utils::Point;
 //!  let p1 = Point::<i32>::from((1, 1));
 //!  let p2 = Point::<i32>::from((2, 3));
 //!  assert_eq!(p1.manhattan_distance(&p2), 3);
+//! ```
 //!
 //! Parse points from strings:
 //!
 //! ```rust
+//! # use utils::Point;
 //!   let input = "1,2";
 //!   let point = Point::<i32>::try_from(input);
 //!   assert!(point.is_ok());
 //!   assert_eq!(point.unwrap(), Point::<i32>::new(1, 2));
+//! ```
 
 use crate::Direction;
 use anyhow::{anyhow, Result};
@@ -203,7 +211,6 @@ impl<
     type Output = Self;
 
     #[inline]
-    #[must_use]
     fn add(self, rhs: Self) -> Self {
         Self::new(self.x + rhs.x, self.y + rhs.y)
     }
@@ -236,7 +243,6 @@ impl<
     type Output = Self;
 
     #[inline]
-    #[must_use]
     fn mul(self, rhs: T) -> Self {
         Point::new(self.x * rhs, self.y * rhs)
     }
@@ -259,7 +265,6 @@ impl<
     type Output = Self;
 
     #[inline]
-    #[must_use]
     fn sub(self, rhs: Self) -> Self {
         let x = self.x.checked_sub(&rhs.x).unwrap_or_default();
         let y = self.y.checked_sub(&rhs.y).unwrap_or_default();
@@ -1,109 +1,197 @@
 //! PriorityQueue
 //! PrioritiyQueue for key, value pairs based on [BinaryHeap].
-//! [BinaryHeap] levearages [Ord] to manage min vs max heap.
+//! Whether the smallest or the largest key comes out first is part of the
+//! type: use [MinQueue] or [MaxQueue].
+//!
+//! Entries with equal keys come out in no particular order, unless the
+//! queue is made with [PriorityQueue::with_fifo], in which case they come
+//! out in the order they were pushed.
+//!
+//! ```rust
+//! # use utils::MinQueue;
+//! let mut queue = MinQueue::<u32, char>::new().with_fifo();
+//! queue.extend([(2, 'a'), (1, 'b'), (1, 'c')]);
+//! assert_eq!(queue.peek(), Some((&1, &'b')));
+//! assert_eq!(queue.into_sorted_vec(), vec![(1, 'b'), (1, 'c'), (2, 'a')]);
+//! ```
+use std::cmp::Ordering;
 use std::collections::BinaryHeap;
+use std::marker::PhantomData;
 
-/// Key, Value wrapper for the PriorityQueue
-#[derive(Debug)]
-pub struct Entry<K: Ord, V> {
-    min: bool,
-    key: K,
-    value: V,
+/// Decides which of two keys leaves a queue first.
+pub trait QueueOrder {
+    /// [Ordering::Greater] means `a` comes out before `b`.
+    fn cmp<K: Ord>(a: &K, b: &K) -> Ordering;
 }
 
-impl<K: Ord, V> Entry<K, V> {
-    pub fn new(min: bool, key: K, value: V) -> Self {
-        Self { min, key, value }
+/// Smallest key first
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Min;
+
+/// Largest key first
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Max;
+
+impl QueueOrder for Min {
+    #[inline]
+    fn cmp<K: Ord>(a: &K, b: &K) -> Ordering {
+        b.cmp(a)
     }
 }
 
-impl<K: Ord, V> PartialEq for Entry<K, V> {
+impl QueueOrder for Max {
+    #[inline]
+    fn cmp<K: Ord>(a: &K, b: &K) -> Ordering {
+        a.cmp(b)
+    }
+}
+
+/// Key, Value wrapper for the PriorityQueue.  `seq` breaks ties between
+/// equal keys: lower sequence numbers come out first.
+#[derive(Debug)]
+struct Entry<K, V, O> {
+    key: K,
+    seq: u64,
+    value: V,
+    order: PhantomData<O>,
+}
+
+impl<K: Ord, V, O: QueueOrder> PartialEq for Entry<K, V, O> {
     fn eq(&self, other: &Self) -> bool {
-        self.key == other.key
+        self.cmp(other) == Ordering::Equal
     }
 }
 
-impl<K: Ord, V> Eq for Entry<K, V> {}
+impl<K: Ord, V, O: QueueOrder> Eq for Entry<K, V, O> {}
 
-impl<K: Ord, V> PartialOrd for Entry<K, V> {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+impl<K: Ord, V, O: QueueOrder> PartialOrd for Entry<K, V, O> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
     }
 }
 
-impl<K: Ord, V> Ord for Entry<K, V> {
-    /// To implement a max heap, do self.key.cmp(other.key)
-    /// To implement a min heap, reverse it.
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        match self.min {
-            true => other.key.cmp(&self.key),
-            false => self.key.cmp(&other.key),
-        }
+impl<K: Ord, V, O: QueueOrder> Ord for Entry<K, V, O> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        O::cmp(&self.key, &other.key).then_with(|| other.seq.cmp(&self.seq))
     }
 }
 
 #[derive(Debug)]
-pub struct PriorityQueue<K: Ord, V> {
-    min: bool,
-    heap: BinaryHeap<Entry<K, V>>,
+pub struct PriorityQueue<K: Ord, V, O: QueueOrder = Min> {
+    heap: BinaryHeap<Entry<K, V, O>>,
+    fifo: bool,
+    next_seq: u64,
 }
 
-impl<K: Ord, V> Default for PriorityQueue<K, V> {
+/// Pops the smallest key first
+pub type MinQueue<K, V> = PriorityQueue<K, V, Min>;
+
+/// Pops the largest key first
+pub type MaxQueue<K, V> = PriorityQueue<K, V, Max>;
+
+impl<K: Ord, V, O: QueueOrder> Default for PriorityQueue<K, V, O> {
     fn default() -> Self {
-        Self {
-            min: true,
-            heap: BinaryHeap::new(),
-        }
+        Self::new()
     }
 }
 
-impl<K: Ord, V> PriorityQueue<K, V> {
-    pub fn new(min: bool) -> Self {
+impl<K: Ord, V, O: QueueOrder> PriorityQueue<K, V, O> {
+    pub fn new() -> Self {
         Self {
-            min,
-            heap: BinaryHeap::<Entry<K, V>>::new(),
+            heap: BinaryHeap::new(),
+            fifo: false,
+            next_seq: 0,
         }
     }
-    pub fn with_capacity(min: bool, capacity: usize) -> Self {
+
+    pub fn with_capacity(capacity: usize) -> Self {
         Self {
-            min,
             heap: BinaryHeap::with_capacity(capacity),
+            fifo: false,
+            next_seq: 0,
         }
     }
 
+    /// Pop entries with equal keys in the order they were pushed.
+    pub fn with_fifo(mut self) -> Self {
+        self.fifo = true;
+        self
+    }
+
     pub fn push(&mut self, key: K, value: V) {
-        self.heap.push(Entry::<K, V>::new(self.min, key, value))
+        let seq = self.next_seq;
+        if self.fifo {
+            self.next_seq += 1;
+        }
+        self.heap.push(Entry {
+            key,
+            seq,
+            value,
+            order: PhantomData,
+        })
     }
 
     pub fn pop(&mut self) -> Option<(K, V)> {
         self.heap.pop().map(|entry| (entry.key, entry.value))
     }
 
-    pub fn keys(&self) -> Vec<&K> {
-        self.heap.iter().map(|entry| &entry.key).collect()
+    /// Return the entry that would be popped next
+    pub fn peek(&self) -> Option<(&K, &V)> {
+        self.heap.peek().map(|entry| (&entry.key, &entry.value))
     }
 
-    pub fn values(&self) -> Vec<&V> {
-        self.heap.iter().map(|entry| &entry.value).collect()
+    pub fn len(&self) -> usize {
+        self.heap.len()
     }
-    pub fn entries(&self) -> Vec<(&K, &V)> {
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.heap.clear();
+    }
+
+    /// The keys, in no particular order
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.heap.iter().map(|entry| &entry.key)
+    }
+
+    /// The values, in no particular order
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.heap.iter().map(|entry| &entry.value)
+    }
+
+    /// The entries, in no particular order
+    pub fn entries(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.heap.iter().map(|entry| (&entry.key, &entry.value))
+    }
+
+    /// Consume the queue, returning the entries in the order they would
+    /// have been popped.
+    pub fn into_sorted_vec(self) -> Vec<(K, V)> {
         self.heap
-            .iter()
-            .map(|entry| (&entry.key, &entry.value))
+            .into_sorted_vec()
+            .into_iter()
+            .rev()
+            .map(|entry| (entry.key, entry.value))
             .collect()
     }
 }
 
-/// Treat PriorityQueue like a BinaryHeap
-impl<K: Ord, V> std::ops::Deref for PriorityQueue<K, V> {
-    type Target = BinaryHeap<Entry<K, V>>;
-    fn deref(&self) -> &Self::Target {
-        &self.heap
+impl<K: Ord, V, O: QueueOrder> Extend<(K, V)> for PriorityQueue<K, V, O> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.push(key, value);
+        }
     }
 }
-impl<K: Ord, V> std::ops::DerefMut for PriorityQueue<K, V> {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.heap
+
+impl<K: Ord, V, O: QueueOrder> FromIterator<(K, V)> for PriorityQueue<K, V, O> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut queue = Self::new();
+        queue.extend(iter);
+        queue
     }
 }
 
@@ -113,33 +201,48 @@ mod tests {
 
     #[test]
     fn test_it() {
-        let min = true;
-        let mut min_heap = PriorityQueue::<u32, u32>::new(min);
+        let mut min_heap = MinQueue::<u32, u32>::new();
 
         min_heap.push(2, 0);
         min_heap.push(1, 1);
         min_heap.push(1, 3);
         min_heap.push(1, 2);
 
-        assert_eq!(min_heap.keys(), vec![&1u32, &1u32, &1u32, &2u32]);
-        assert_eq!(min_heap.values(), vec![&1u32, &2u32, &3u32, &0u32]);
-        assert_eq!(
-            min_heap.entries(),
-            vec![
-                (&1u32, &1u32),
-                (&1u32, &2u32),
-                (&1u32, &3u32),
-                (&2u32, &0u32)
-            ]
-        );
-
-        assert_eq!(min_heap.pop(), Some((1, 1)));
-        assert_eq!(min_heap.pop(), Some((1, 3)));
-        assert_eq!(min_heap.pop(), Some((1, 2)));
+        let mut keys = min_heap.keys().copied().collect::<Vec<u32>>();
+        keys.sort();
+        assert_eq!(keys, vec![1, 1, 1, 2]);
+        let mut values = min_heap.values().copied().collect::<Vec<u32>>();
+        values.sort();
+        assert_eq!(values, vec![0, 1, 2, 3]);
+        assert_eq!(min_heap.entries().count(), 4);
+        assert_eq!(min_heap.peek().map(|(key, _)| *key), Some(1));
+
+        // Equal keys come out in any order, but before the larger key
+        let mut ones = (0..3).map(|_| min_heap.pop().unwrap()).collect::<Vec<_>>();
+        ones.sort();
+        assert_eq!(ones, vec![(1, 1), (1, 2), (1, 3)]);
         assert_eq!(min_heap.pop(), Some((2, 0)));
+        assert_eq!(min_heap.pop(), None);
 
-        // By implementing Deref, we can treat the PiorityQueue like a BinaryHeap
+        min_heap.push(1, 1);
         min_heap.clear();
         assert!(min_heap.is_empty());
     }
+
+    #[test]
+    fn test_fifo() {
+        let mut max_heap = MaxQueue::<u32, char>::new().with_fifo();
+        max_heap.extend([(1, 'a'), (2, 'b'), (1, 'c'), (2, 'd'), (1, 'e')]);
+        assert_eq!(max_heap.len(), 5);
+        assert_eq!(
+            max_heap.into_sorted_vec(),
+            vec![(2, 'b'), (2, 'd'), (1, 'a'), (1, 'c'), (1, 'e')]
+        );
+
+        let min_heap: MinQueue<u32, char> = [(3, 'c'), (1, 'a'), (2, 'b')].into_iter().collect();
+        assert_eq!(
+            min_heap.into_sorted_vec(),
+            vec![(1, 'a'), (2, 'b'), (3, 'c')]
+        );
+    }
 }
@@ -0,0 +1,170 @@
+//! Search
+//! Graph searches that are generic over the queue used for the frontier.
+//! [dijkstra] works with a [MinQueue], an [IndexedPriorityQueue], or, when
+//! the edge costs are small integers, a [BucketQueue].
+//!
+//! ```rust
+//! # use utils::{dijkstra, BucketQueue, MinQueue};
+//! // 0 -> 1 -> 2 costs 2, 0 -> 2 costs 5
+//! let successors = |node: &u32| match node {
+//!     0 => vec![(1, 1), (2, 5)],
+//!     1 => vec![(2, 1)],
+//!     _ => vec![],
+//! };
+//! let heap = dijkstra(MinQueue::new(), 0, |node| *node == 2, successors);
+//! let buckets = dijkstra(BucketQueue::new(), 0, |node| *node == 2, successors);
+//! assert_eq!(heap, Some((2u32, vec![0, 1, 2])));
+//! assert_eq!(heap, buckets);
+//! ```
+use super::{BucketQueue, IndexedPriorityQueue, Min, PriorityQueue};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// A min priority queue that can hold the frontier of a search.
+pub trait Frontier<K, V> {
+    fn push(&mut self, key: K, value: V);
+    fn pop(&mut self) -> Option<(K, V)>;
+    fn is_empty(&self) -> bool;
+}
+
+impl<K: Ord, V> Frontier<K, V> for PriorityQueue<K, V, Min> {
+    fn push(&mut self, key: K, value: V) {
+        PriorityQueue::push(self, key, value)
+    }
+
+    fn pop(&mut self) -> Option<(K, V)> {
+        PriorityQueue::pop(self)
+    }
+
+    fn is_empty(&self) -> bool {
+        PriorityQueue::is_empty(self)
+    }
+}
+
+/// Pushing a value that is already queued only ever lowers its key.
+impl<K: Ord, V: Hash + Eq + Clone> Frontier<K, V> for IndexedPriorityQueue<K, V, Min> {
+    fn push(&mut self, key: K, value: V) {
+        self.push_or_update(key, value);
+    }
+
+    fn pop(&mut self) -> Option<(K, V)> {
+        IndexedPriorityQueue::pop(self)
+    }
+
+    fn is_empty(&self) -> bool {
+        IndexedPriorityQueue::is_empty(self)
+    }
+}
+
+impl<K: num::PrimInt, V> Frontier<K, V> for BucketQueue<K, V> {
+    fn push(&mut self, key: K, value: V) {
+        BucketQueue::push(self, key, value)
+    }
+
+    fn pop(&mut self) -> Option<(K, V)> {
+        BucketQueue::pop(self)
+    }
+
+    fn is_empty(&self) -> bool {
+        BucketQueue::is_empty(self)
+    }
+}
+
+/// Find the cheapest path from `start` to the first node for which `is_goal`
+/// returns true.  `successors` returns each neighbor of a node with the cost
+/// of the step to it.  Returns the total cost and the path, including both
+/// `start` and the goal, or None if no goal can be reached.
+///
+/// Entries that are superseded by a cheaper path are skipped when popped, so
+/// any [Frontier] works, including ones that hold duplicates.
+pub fn dijkstra<Q, N, K, G, F, I>(
+    mut frontier: Q,
+    start: N,
+    is_goal: G,
+    mut successors: F,
+) -> Option<(K, Vec<N>)>
+where
+    Q: Frontier<K, N>,
+    N: Hash + Eq + Clone,
+    K: Ord + Copy + num::Zero,
+    G: Fn(&N) -> bool,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, K)>,
+{
+    // The cheapest known cost to each node, and the node it was reached from
+    let mut best: HashMap<N, (K, Option<N>)> = HashMap::new();
+    best.insert(start.clone(), (K::zero(), None));
+    frontier.push(K::zero(), start);
+
+    while let Some((cost, node)) = frontier.pop() {
+        if best.get(&node).is_some_and(|(known, _)| cost > *known) {
+            // Stale
+            continue;
+        }
+        if is_goal(&node) {
+            let path = extract_path(&best, node);
+            return Some((cost, path));
+        }
+        for (next, step) in successors(&node) {
+            let new_cost = cost + step;
+            if best.get(&next).is_none_or(|(known, _)| new_cost < *known) {
+                best.insert(next.clone(), (new_cost, Some(node.clone())));
+                frontier.push(new_cost, next);
+            }
+        }
+    }
+    None
+}
+
+fn extract_path<N: Hash + Eq + Clone, K>(best: &HashMap<N, (K, Option<N>)>, goal: N) -> Vec<N> {
+    let mut path = vec![goal];
+    while let Some((_, Some(prev))) = best.get(path.last().expect("Not empty")) {
+        path.push(prev.clone());
+    }
+    path.reverse();
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Grid, MinQueue, Point};
+
+    #[test]
+    fn test_frontiers_agree() {
+        let input = "19111\n11191\n99991\n11111\n19999";
+        let grid =
+            Grid::<i32, u32>::parse_undelim_str(input, |c| Ok(c as u32 - '0' as u32)).unwrap();
+        let goal = Point::new(4, 4);
+        let successors = |point: &Point<i32>| {
+            grid.neighbors(point)
+                .map(|next| (next, *grid.get(&next).unwrap()))
+                .collect::<Vec<_>>()
+        };
+        let is_goal = |point: &Point<i32>| *point == goal;
+
+        let heap = dijkstra(MinQueue::new(), Point::origin(), is_goal, successors);
+        let indexed = dijkstra(
+            IndexedPriorityQueue::new(),
+            Point::origin(),
+            is_goal,
+            successors,
+        );
+        let buckets = dijkstra(BucketQueue::new(), Point::origin(), is_goal, successors);
+
+        let (cost, path) = heap.unwrap();
+        assert_eq!(cost, 18);
+        assert_eq!(path.first(), Some(&Point::origin()));
+        assert_eq!(path.last(), Some(&goal));
+        assert_eq!(indexed.unwrap().0, cost);
+        assert_eq!(buckets.unwrap().0, cost);
+
+        let nowhere = dijkstra(
+            MinQueue::new(),
+            0u32,
+            |_| false,
+            |_: &u32| vec![(1u32, 1u32)],
+        );
+        assert!(nowhere.is_none());
+    }
+}
@@ -0,0 +1,172 @@
+//! TiledGrid
+//! A read only view over a [Grid] that treats the grid as repeating
+//! infinitely in every direction.  Any `Point<i64>` maps onto a cell of the
+//! base grid with [i64::rem_euclid], and [i64::div_euclid] tells which copy
+//! of the grid (the tile) the point lies in.
+//!
+//! The same view can be used as a torus by working in base coordinates:
+//! [TiledGrid::wrapped_neighbors] wraps across the seams instead of leaving
+//! the base grid.
+//!
+//! An empty grid has nothing to repeat, so it can't be tiled.
+//!
+//! ```rust
+//! # use anyhow::anyhow;
+//! # use utils::{Grid, Point, TiledGrid};
+//! let convert = |c: char| c.to_digit(10).ok_or(anyhow!("Failed to convert"));
+//! let grid = Grid::<i32, u32>::parse_undelim_str("12\n34", convert).unwrap();
+//! let tiled = TiledGrid::new(&grid).unwrap();
+//! let point = Point::<i64>::new(-1, 2);
+//! assert_eq!(tiled.get(&point), &2);
+//! assert_eq!(tiled.tile(&point), Point::<i64>::new(-1, 1));
+//! ```
+use anyhow::{anyhow, Result};
+
+use super::{Direction, Grid, Point};
+
+/// Order in which neighbors are returned.
+const DIRECTIONS: [Direction; 4] = [
+    Direction::North,
+    Direction::East,
+    Direction::South,
+    Direction::West,
+];
+
+#[derive(Debug, Clone, Copy)]
+pub struct TiledGrid<'a, P, T> {
+    grid: &'a Grid<P, T>,
+}
+
+impl<
+        'a,
+        P: std::fmt::Display + num::Integer + num::PrimInt + num::FromPrimitive + Sync + Send,
+        T: std::fmt::Debug + Sized + Send + Sync,
+    > TiledGrid<'a, P, T>
+{
+    /// Fails if the grid has no cells
+    pub fn new(grid: &'a Grid<P, T>) -> Result<Self> {
+        if grid.width() == 0 || grid.height() == 0 {
+            return Err(anyhow!(
+                "Can't tile an empty grid: {}x{}",
+                grid.width(),
+                grid.height()
+            ));
+        }
+        Ok(Self { grid })
+    }
+
+    /// The underlying grid
+    pub fn base(&self) -> &'a Grid<P, T> {
+        self.grid
+    }
+
+    #[inline]
+    fn size(&self) -> Point<i64> {
+        Point::<i64>::from((self.grid.width(), self.grid.height()))
+    }
+
+    /// Map any point onto the matching point of the base grid.
+    #[inline]
+    pub fn wrap(&self, point: &Point<i64>) -> Point<i64> {
+        let size = self.size();
+        Point::new(point.x.rem_euclid(size.x), point.y.rem_euclid(size.y))
+    }
+
+    /// Return which copy of the base grid the point lies in.  The base grid
+    /// itself is tile `[0, 0]`, the copy to its left is `[-1, 0]`.
+    #[inline]
+    pub fn tile(&self, point: &Point<i64>) -> Point<i64> {
+        let size = self.size();
+        Point::new(point.x.div_euclid(size.x), point.y.div_euclid(size.y))
+    }
+
+    /// Return a reference to the value at any point.  This can't fail, since
+    /// every point maps onto the base grid.
+    pub fn get(&self, point: &Point<i64>) -> &'a T {
+        let base = self.wrap(point);
+        self.grid
+            .get_at(base.x as usize, base.y as usize)
+            .expect("Wrapped point is always in bounds")
+    }
+
+    /// Return the four neighbors of the point on the infinite plane, in
+    /// North, East, South, West order.  Neighbors may lie in another tile.
+    pub fn neighbors(&self, point: &Point<i64>) -> [Point<i64>; 4] {
+        DIRECTIONS.map(|direction| point.step(direction))
+    }
+
+    /// Return the four neighbors of the point on the torus, in North, East,
+    /// South, West order.  Neighbors that cross a seam wrap around to the
+    /// other side of the base grid.
+    pub fn wrapped_neighbors(&self, point: &Point<i64>) -> [Point<i64>; 4] {
+        DIRECTIONS.map(|direction| self.wrap(&point.step(direction)))
+    }
+}
+
+impl<
+        P: std::fmt::Display + num::Integer + num::PrimInt + num::FromPrimitive + Sync + Send,
+        T: std::fmt::Debug + Sized + Send + Sync,
+    > Grid<P, T>
+{
+    /// View this grid as infinitely repeating.  See [TiledGrid].
+    pub fn tiled(&self) -> Result<TiledGrid<'_, P, T>> {
+        TiledGrid::new(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid() -> Grid<i32, char> {
+        Grid::parse_undelim_str("abc\ndef", Ok).unwrap()
+    }
+
+    #[test]
+    fn test_wrap() {
+        let grid = grid();
+        let tiled = grid.tiled().unwrap();
+        assert_eq!(tiled.get(&Point::new(0, 0)), &'a');
+        assert_eq!(tiled.get(&Point::new(3, 0)), &'a');
+        assert_eq!(tiled.get(&Point::new(-1, -1)), &'f');
+        assert_eq!(tiled.get(&Point::new(-4, 3)), &'f');
+
+        assert_eq!(tiled.tile(&Point::new(2, 1)), Point::new(0, 0));
+        assert_eq!(tiled.tile(&Point::new(3, -1)), Point::new(1, -1));
+        assert_eq!(tiled.tile(&Point::new(-3, -2)), Point::new(-1, -1));
+        assert_eq!(tiled.tile(&Point::new(-4, 4)), Point::new(-2, 2));
+    }
+
+    #[test]
+    fn test_empty() {
+        let grid = Grid::<i32, char>::from_cells(Vec::new(), 0, 0);
+        assert!(grid.tiled().is_err());
+        let grid = Grid::<i32, char>::from_cells(Vec::new(), 3, 0);
+        assert!(TiledGrid::new(&grid).is_err());
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let grid = grid();
+        let tiled = grid.tiled().unwrap();
+        let origin = Point::<i64>::origin();
+        assert_eq!(
+            tiled.neighbors(&origin),
+            [
+                Point::new(0, -1),
+                Point::new(1, 0),
+                Point::new(0, 1),
+                Point::new(-1, 0)
+            ]
+        );
+        assert_eq!(
+            tiled.wrapped_neighbors(&origin),
+            [
+                Point::new(0, 1),
+                Point::new(1, 0),
+                Point::new(0, 1),
+                Point::new(2, 0)
+            ]
+        );
+    }
+}
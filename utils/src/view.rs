@@ -0,0 +1,306 @@
+//! GridView
+//! A zero copy, read only view over a rectangular window of a [Grid], in
+//! any of the eight orientations reachable by rotating and flipping.
+//! Transforms compose, so `grid.view().transpose().flip_h()` is the same
+//! view as `grid.view().rotate_cw()`.
+//!
+//! Owned versions of each transform are available directly on [Grid] when
+//! `T: Clone`.
+//!
+//! ```rust
+//! # use utils::Grid;
+//! let grid = Grid::<i32, char>::parse_undelim_str("abc\ndef", Ok).unwrap();
+//! let view = grid.view().rotate_cw();
+//! assert_eq!(view.width(), 2);
+//! assert_eq!(view.height(), 3);
+//! assert_eq!(view.row(0).collect::<String>(), "da");
+//!
+//! let window = grid.window(1, 0, 2, 2).unwrap();
+//! assert_eq!(window.row(1).collect::<String>(), "ef");
+//! ```
+use super::Grid;
+use anyhow::{anyhow, Result};
+
+#[derive(Debug)]
+pub struct GridView<'a, P, T> {
+    grid: &'a Grid<P, T>,
+    /// Window origin and size, in grid coordinates
+    left: usize,
+    top: usize,
+    cols: usize,
+    rows: usize,
+    /// Orientation: view coordinates are flipped, then optionally swapped,
+    /// to get window coordinates.
+    swap: bool,
+    flip_x: bool,
+    flip_y: bool,
+}
+
+/// Views only borrow the grid, so copying doesn't require `T: Copy`.
+impl<'a, P, T> Clone for GridView<'a, P, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, P, T> Copy for GridView<'a, P, T> {}
+
+impl<
+        'a,
+        P: std::fmt::Display + num::Integer + num::PrimInt + num::FromPrimitive + Sync + Send,
+        T: std::fmt::Debug + Sized + Send + Sync,
+    > GridView<'a, P, T>
+{
+    pub fn new(grid: &'a Grid<P, T>) -> Self {
+        Self {
+            grid,
+            left: 0,
+            top: 0,
+            cols: grid.width(),
+            rows: grid.height(),
+            swap: false,
+            flip_x: false,
+            flip_y: false,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        match self.swap {
+            true => self.rows,
+            false => self.cols,
+        }
+    }
+
+    pub fn height(&self) -> usize {
+        match self.swap {
+            true => self.cols,
+            false => self.rows,
+        }
+    }
+
+    /// Map view coordinates to grid coordinates.
+    #[inline]
+    fn map(&self, x: usize, y: usize) -> (usize, usize) {
+        let x = if self.flip_x { self.width() - 1 - x } else { x };
+        let y = if self.flip_y {
+            self.height() - 1 - y
+        } else {
+            y
+        };
+        let (x, y) = if self.swap { (y, x) } else { (x, y) };
+        (self.left + x, self.top + y)
+    }
+
+    #[inline]
+    fn cell(&self, x: usize, y: usize) -> &'a T {
+        let (x, y) = self.map(x, y);
+        &self.grid.cells[y * self.grid.width() + x]
+    }
+
+    pub fn get_at(&self, x: usize, y: usize) -> Result<&'a T> {
+        if x >= self.width() || y >= self.height() {
+            return Err(anyhow!("Out of bounds: {},{}", x, y));
+        }
+        Ok(self.cell(x, y))
+    }
+
+    /// Iterate over the cells of row `y`, left to right.
+    pub fn row(&self, y: usize) -> impl ExactSizeIterator<Item = &'a T> {
+        let view = *self;
+        (0..self.width()).map(move |x| view.cell(x, y))
+    }
+
+    /// Iterate over the rows, top to bottom.
+    pub fn rows(&self) -> impl ExactSizeIterator<Item = impl ExactSizeIterator<Item = &'a T>> {
+        let view = *self;
+        (0..self.height()).map(move |y| view.row(y))
+    }
+
+    /// Swap rows and columns
+    pub fn transpose(self) -> Self {
+        Self {
+            swap: !self.swap,
+            flip_x: self.flip_y,
+            flip_y: self.flip_x,
+            ..self
+        }
+    }
+
+    /// Mirror left to right
+    pub fn flip_h(self) -> Self {
+        Self {
+            flip_x: !self.flip_x,
+            ..self
+        }
+    }
+
+    /// Mirror top to bottom
+    pub fn flip_v(self) -> Self {
+        Self {
+            flip_y: !self.flip_y,
+            ..self
+        }
+    }
+
+    /// Rotate 90 degrees clockwise
+    pub fn rotate_cw(self) -> Self {
+        self.transpose().flip_h()
+    }
+
+    /// Rotate 90 degrees counter clockwise
+    pub fn rotate_ccw(self) -> Self {
+        self.transpose().flip_v()
+    }
+
+    /// Return a view of the `width` x `height` window whose top left corner
+    /// is at `x`, `y` in this view.  The window keeps this view's orientation.
+    pub fn window(self, x: usize, y: usize, width: usize, height: usize) -> Result<Self> {
+        let fits = |start: usize, len: usize, limit: usize| {
+            len > 0 && start.checked_add(len).is_some_and(|end| end <= limit)
+        };
+        if !fits(x, width, self.width()) || !fits(y, height, self.height()) {
+            return Err(anyhow!(
+                "Window {}x{} at {},{} is out of bounds",
+                width,
+                height,
+                x,
+                y
+            ));
+        }
+        let (x1, y1) = self.map(x, y);
+        let (x2, y2) = self.map(x + width - 1, y + height - 1);
+        let (cols, rows) = if self.swap {
+            (height, width)
+        } else {
+            (width, height)
+        };
+        Ok(Self {
+            left: x1.min(x2),
+            top: y1.min(y2),
+            cols,
+            rows,
+            ..self
+        })
+    }
+
+    /// Copy the view into a new grid
+    pub fn to_grid(&self) -> Grid<P, T>
+    where
+        T: Clone,
+    {
+        let cells = self.rows().flatten().cloned().collect::<Vec<T>>();
+        Grid::from_cells(cells, self.width(), self.height())
+    }
+}
+
+impl<
+        P: std::fmt::Display + num::Integer + num::PrimInt + num::FromPrimitive + Sync + Send,
+        T: std::fmt::Debug + Sized + Send + Sync,
+    > Grid<P, T>
+{
+    /// View the whole grid.  See [GridView].
+    pub fn view(&self) -> GridView<'_, P, T> {
+        GridView::new(self)
+    }
+
+    /// View the `width` x `height` window whose top left corner is at `x`, `y`.
+    pub fn window(
+        &self,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+    ) -> Result<GridView<'_, P, T>> {
+        self.view().window(x, y, width, height)
+    }
+
+    pub fn transpose(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.view().transpose().to_grid()
+    }
+
+    pub fn rotate_cw(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.view().rotate_cw().to_grid()
+    }
+
+    pub fn rotate_ccw(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.view().rotate_ccw().to_grid()
+    }
+
+    pub fn flip_h(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.view().flip_h().to_grid()
+    }
+
+    pub fn flip_v(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.view().flip_v().to_grid()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid() -> Grid<i32, char> {
+        Grid::parse_undelim_str("abc\ndef", Ok).unwrap()
+    }
+
+    fn lines(view: GridView<i32, char>) -> Vec<String> {
+        view.rows().map(|row| row.collect()).collect()
+    }
+
+    #[test]
+    fn test_transforms() {
+        let grid = grid();
+        assert_eq!(lines(grid.view()), vec!["abc", "def"]);
+        assert_eq!(lines(grid.view().transpose()), vec!["ad", "be", "cf"]);
+        assert_eq!(lines(grid.view().rotate_cw()), vec!["da", "eb", "fc"]);
+        assert_eq!(lines(grid.view().rotate_ccw()), vec!["cf", "be", "ad"]);
+        assert_eq!(lines(grid.view().flip_h()), vec!["cba", "fed"]);
+        assert_eq!(lines(grid.view().flip_v()), vec!["def", "abc"]);
+        assert_eq!(
+            lines(grid.view().rotate_cw().rotate_cw()),
+            vec!["fed", "cba"]
+        );
+        assert_eq!(
+            lines(grid.view().rotate_cw().rotate_ccw()),
+            lines(grid.view())
+        );
+
+        let rotated = grid.rotate_cw();
+        assert_eq!(rotated.width(), 2);
+        assert_eq!(rotated.cells, vec!['d', 'a', 'e', 'b', 'f', 'c']);
+        assert_eq!(rotated.rotate_ccw().cells, grid.cells);
+    }
+
+    #[test]
+    fn test_window() {
+        let grid = Grid::<i32, char>::parse_undelim_str("abcd\nefgh\nijkl", Ok).unwrap();
+        let window = grid.window(1, 1, 2, 2).unwrap();
+        assert_eq!(lines(window), vec!["fg", "jk"]);
+        assert_eq!(lines(window.rotate_cw()), vec!["jf", "kg"]);
+
+        // Windows of a transformed view are in view coordinates
+        let window = grid.view().rotate_cw().window(0, 1, 3, 2).unwrap();
+        assert_eq!(lines(window), vec!["jfb", "kgc"]);
+        assert_eq!(window.get_at(2, 1).unwrap(), &'c');
+
+        assert!(grid.window(3, 0, 2, 1).is_err());
+        assert!(grid.window(1, 0, usize::MAX, 1).is_err());
+        assert!(grid.window(0, usize::MAX, 1, 2).is_err());
+        assert!(window.get_at(3, 0).is_err());
+    }
+}
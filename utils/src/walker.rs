@@ -0,0 +1,175 @@
+//! Walker
+//! A position and heading that moves over a [Grid], or anything else that
+//! is [GridLike].  The walker only borrows the grid, so any number of
+//! walkers can move over the same grid at once.
+//!
+//! ```rust
+//! # use utils::{Direction, Grid, GridLike, Point};
+//! let grid = Grid::<i32, char>::parse_undelim_str("ab\ncd", Ok).unwrap();
+//! let mut walker = grid.walker(Point::origin(), Direction::East).with_history();
+//! assert_eq!(walker.step(), Some(Point::new(1, 0)));
+//! // Stepping off the grid leaves the walker where it is.
+//! assert_eq!(walker.step(), None);
+//! walker.turn_right();
+//! walker.step();
+//! assert_eq!(walker.get().unwrap(), &'d');
+//! assert_eq!(walker.history(), &[Point::new(0, 0), Point::new(1, 0), Point::new(1, 1)]);
+//! ```
+use super::{grid::try_step, Direction, GridLike, Point};
+use anyhow::Result;
+
+pub struct Walker<'a, P, T> {
+    grid: &'a dyn GridLike<T>,
+    position: Point<P>,
+    heading: Direction,
+    history: Option<Vec<Point<P>>>,
+}
+
+/// The grid is left out, since any [GridLike] will do.
+impl<'a, P: std::fmt::Debug, T> std::fmt::Debug for Walker<'a, P, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Walker")
+            .field("position", &self.position)
+            .field("heading", &self.heading)
+            .field("history", &self.history)
+            .finish()
+    }
+}
+
+/// Walkers only borrow the grid, so cloning doesn't require `T: Clone`.
+impl<'a, P: Copy, T> Clone for Walker<'a, P, T> {
+    fn clone(&self) -> Self {
+        Self {
+            grid: self.grid,
+            position: self.position,
+            heading: self.heading,
+            history: self.history.clone(),
+        }
+    }
+}
+
+impl<
+        'a,
+        P: std::fmt::Display + num::Integer + num::PrimInt + num::FromPrimitive + Sync + Send,
+        T: std::fmt::Debug + Sized + Send + Sync,
+    > Walker<'a, P, T>
+{
+    pub fn new(grid: &'a dyn GridLike<T>, position: Point<P>, heading: Direction) -> Self {
+        Self {
+            grid,
+            position,
+            heading,
+            history: None,
+        }
+    }
+
+    /// Record every position the walker visits, starting with the current
+    /// one.
+    pub fn with_history(mut self) -> Self {
+        self.history = Some(vec![self.position]);
+        self
+    }
+
+    pub fn grid(&self) -> &'a dyn GridLike<T> {
+        self.grid
+    }
+
+    pub fn position(&self) -> Point<P> {
+        self.position
+    }
+
+    pub fn heading(&self) -> Direction {
+        self.heading
+    }
+
+    /// Positions visited so far.  Empty unless [Walker::with_history] was used.
+    pub fn history(&self) -> &[Point<P>] {
+        self.history.as_deref().unwrap_or_default()
+    }
+
+    /// Return a reference to the value under the walker
+    pub fn get(&self) -> Result<&'a T>
+    where
+        usize: std::convert::TryFrom<P>,
+    {
+        let index = self.position.indexible()?;
+        self.grid.get_at(index.x, index.y)
+    }
+
+    pub fn face(&mut self, heading: Direction) {
+        self.heading = heading;
+    }
+
+    pub fn turn_left(&mut self) {
+        self.heading = self.heading.turn_left();
+    }
+
+    pub fn turn_right(&mut self) {
+        self.heading = self.heading.turn_right();
+    }
+
+    pub fn reverse(&mut self) {
+        self.heading = self.heading.reverse();
+    }
+
+    /// Return the point one step ahead, if it is on the grid, without moving.
+    pub fn peek(&self) -> Option<Point<P>> {
+        try_step(
+            self.grid.width(),
+            self.grid.height(),
+            &self.position,
+            self.heading,
+        )
+    }
+
+    /// Move one step in the current heading.  If that would leave the grid,
+    /// the walker doesn't move and None is returned.
+    pub fn step(&mut self) -> Option<Point<P>> {
+        let next = self.peek()?;
+        self.position = next;
+        if let Some(history) = self.history.as_mut() {
+            history.push(next);
+        }
+        Some(next)
+    }
+
+    /// Face `heading`, then step.
+    pub fn step_in(&mut self, heading: Direction) -> Option<Point<P>> {
+        self.face(heading);
+        self.step()
+    }
+
+    /// Return a copy of this walker facing `heading`.  Useful when a path
+    /// splits.
+    pub fn fork(&self, heading: Direction) -> Self {
+        let mut walker = self.clone();
+        walker.heading = heading;
+        walker
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Grid, GridLike};
+
+    #[test]
+    fn test_walkers() {
+        let grid = Grid::<i32, char>::parse_undelim_str("abc\ndef\nghi", Ok).unwrap();
+        let mut north = grid.walker(Point::new(1, 1), Direction::North);
+        let mut south = north.fork(Direction::South);
+
+        assert_eq!(north.step(), Some(Point::new(1, 0)));
+        assert_eq!(north.step(), None);
+        assert_eq!(north.get().unwrap(), &'b');
+
+        assert_eq!(south.step(), Some(Point::new(1, 2)));
+        south.turn_left();
+        assert_eq!(south.heading(), Direction::East);
+        assert_eq!(south.step(), Some(Point::new(2, 2)));
+        south.reverse();
+        assert_eq!(south.peek(), Some(Point::new(1, 2)));
+        assert_eq!(south.position(), Point::new(2, 2));
+        assert!(south.history().is_empty());
+    }
+}